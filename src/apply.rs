@@ -0,0 +1,103 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use clap::ValueEnum;
+use crate::sway::config::Config;
+use crate::sway::ipc::{CommandResult, Connection, IpcError};
+
+/// Sections of the config that can be applied to a running Sway instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Section {
+    /// Window border and title bar colors (`client.*`)
+    Client,
+    /// Output configuration (`output`)
+    Output,
+    /// Top-level key bindings (`bindsym`)
+    Bindings,
+}
+
+/// Build the commands for the selected sections, with all variables substituted (the running
+/// Sway instance may not have the same variables defined).
+pub fn commands(cfg: &Config, sections: &[Section]) -> Vec<String> {
+    let vars = cfg.variables();
+    sections.iter()
+        .flat_map(|s| match s {
+            Section::Client => cfg.client_commands(),
+            Section::Output => cfg.output_commands(),
+            Section::Bindings => cfg.bindsym_commands(),
+        })
+        .map(|c| vars.substitute(&c.to_string()))
+        .collect()
+}
+
+/// Send each command to Sway separately, so that every rejected command can be reported.
+pub fn send(conn: &mut Connection, commands: &[String]) -> Result<Vec<(String, CommandResult)>, IpcError> {
+    let mut results = Vec::new();
+    for command in commands {
+        log::debug!("Running command: {}", command);
+        for result in conn.run_command(command)? {
+            results.push((command.clone(), result));
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sway::ipc::tests::fake_server;
+
+    fn config() -> Config {
+        toml::from_str("exec-always = [\"kanshi\"]\
+            \n[set]\
+            \nbg = \"#285577\"\
+            \n[client.focused]\
+            \nborder = \"#4c7899\"\
+            \nbackground = \"$bg\"\
+            \ntext = \"#ffffff\"\
+            \n[output.eDP-1]\
+            \nmode = \"1920x1080@60Hz\"\
+            \nposition = { x = 0, y = 0 }\
+            \nscale = 1.5\
+            \n[output.HDMI-A-1]\
+            \nenable = false").unwrap()
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(commands(&config(), &[Section::Client, Section::Output]), vec![
+            "client.focused #4c7899 #285577 #ffffff",
+            "output HDMI-A-1 disable",
+            "output eDP-1 mode 1920x1080@60Hz position 0 0 scale 1.5",
+        ]);
+        assert!(commands(&config(), &[Section::Bindings]).is_empty());
+    }
+
+    #[test]
+    fn test_send() {
+        let (path, server) = fake_server("apply", vec![
+            (0, "[{\"success\": true}]".to_string()),
+            (0, "[{\"success\": false, \"error\": \"Unknown output HDMI-A-1\"}]".to_string()),
+        ]);
+        let mut conn = Connection::connect_to(&path).unwrap();
+        let cmds = vec!["client.focused #4c7899 #285577 #ffffff".to_string(), "output HDMI-A-1 disable".to_string()];
+        let results = send(&mut conn, &cmds).unwrap();
+        assert!(results[0].1.success);
+        assert_eq!(results[1].0, "output HDMI-A-1 disable");
+        assert_eq!(results[1].1.error.as_deref(), Some("Unknown output HDMI-A-1"));
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+}
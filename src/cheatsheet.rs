@@ -0,0 +1,204 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::BTreeMap;
+use clap::ValueEnum;
+use crate::sway::config::Config;
+use crate::sway::runtime::RuntimeKind;
+
+/// Output formats for cheat sheets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Markdown document with one table per command type
+    Markdown,
+    /// Standalone HTML page
+    Html,
+    /// One binding per line, suitable for piping into rofi or dmenu
+    Text,
+    /// Shortcut list in the `## group // description // keys ##` format read by rofi shortcut
+    /// helpers (such as rofi-i3-shortcut-help)
+    Rofi,
+}
+
+/// A single cheat sheet entry.
+struct Entry {
+    keys: String,
+    command: String,
+    description: Option<String>,
+    group: String,
+}
+
+/// Bindings grouped by mode, and then by command type.
+type Groups = BTreeMap<(bool, String), BTreeMap<RuntimeKind, Vec<Entry>>>;
+
+fn group(cfg: &Config) -> Groups {
+    let vars = cfg.variables();
+    let mut groups = Groups::new();
+    for binding in cfg.bindings() {
+        // sort the default mode before everything else
+        let mode = (binding.mode != "default", vars.substitute(binding.mode));
+        groups.entry(mode).or_default()
+            .entry(binding.bind.command().into()).or_default()
+            .push(Entry {
+                keys: vars.substitute(binding.keys),
+                command: vars.substitute(&binding.bind.command().to_string()),
+                description: binding.bind.description().map(|d| d.to_string()),
+                group: binding.bind.group(),
+            });
+    }
+    for kinds in groups.values_mut() {
+        for entries in kinds.values_mut() {
+            entries.sort_by(|a, b| a.keys.cmp(&b.keys));
+        }
+    }
+    groups
+}
+
+/// Render a cheat sheet of all key bindings in a config.
+pub fn render(cfg: &Config, format: Format) -> String {
+    let groups = group(cfg);
+    match format {
+        Format::Markdown => markdown(&groups),
+        Format::Html => html(&groups),
+        Format::Text => text(&groups),
+        Format::Rofi => rofi(&groups),
+    }
+}
+
+fn markdown(groups: &Groups) -> String {
+    let cell = |s: &str| s.replace('|', "\\|");
+    let mut out = String::from("# Key bindings\n");
+    for ((_, mode), kinds) in groups {
+        out.push_str(&format!("\n## Mode: {mode}\n"));
+        for (kind, entries) in kinds {
+            out.push_str(&format!("\n### {kind}\n\n| Keys | Command | Description |\n| --- | --- | --- |\n"));
+            for e in entries {
+                out.push_str(&format!("| `{}` | `{}` | {} |\n",
+                                      cell(&e.keys), cell(&e.command), cell(e.description.as_deref().unwrap_or(""))));
+            }
+        }
+    }
+    out
+}
+
+fn html(groups: &Groups) -> String {
+    let esc = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+        <title>Key bindings</title>\n<style>\n\
+        body { font-family: sans-serif; }\n\
+        table { border-collapse: collapse; margin-bottom: 1em; }\n\
+        th, td { border: 1px solid #888; padding: 0.2em 0.6em; text-align: left; }\n\
+        kbd, code { font-family: monospace; }\n\
+        </style>\n</head>\n<body>\n<h1>Key bindings</h1>\n");
+    for ((_, mode), kinds) in groups {
+        out.push_str(&format!("<h2>Mode: {}</h2>\n", esc(mode)));
+        for (kind, entries) in kinds {
+            out.push_str(&format!("<h3>{kind}</h3>\n<table>\n<tr><th>Keys</th><th>Command</th><th>Description</th></tr>\n"));
+            for e in entries {
+                out.push_str(&format!("<tr><td><kbd>{}</kbd></td><td><code>{}</code></td><td>{}</td></tr>\n",
+                                      esc(&e.keys), esc(&e.command), esc(e.description.as_deref().unwrap_or(""))));
+            }
+            out.push_str("</table>\n");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn text(groups: &Groups) -> String {
+    let mut out = String::new();
+    for ((_, mode), kinds) in groups {
+        for entries in kinds.values() {
+            for e in entries {
+                let description = e.description.as_deref().unwrap_or(&e.command);
+                out.push_str(&format!("[{mode}] {}: {description}\n", e.keys));
+            }
+        }
+    }
+    out
+}
+
+fn rofi(groups: &Groups) -> String {
+    let mut entries: Vec<(String, &Entry)> = Vec::new();
+    for ((_, mode), kinds) in groups {
+        for e in kinds.values().flatten() {
+            // bindings in other modes are only reachable from that mode, so say so
+            let group = if mode == "default" { e.group.clone() } else { format!("{} ({mode} mode)", e.group) };
+            entries.push((group, e));
+        }
+    }
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries.iter()
+        .map(|(group, e)| format!("## {group} // {} // {} ##\n", e.description.as_deref().unwrap_or(&e.command), e.keys))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str("[set]\
+            \nmod = \"Mod4\"\
+            \n[bindsym]\
+            \n\"$mod+Return\" = { exec = \"alacritty\", description = \"Open a terminal\" }\
+            \n\"$mod+Shift+q\".kill = {}\
+            \n\"$mod+Left\".focus.directional = \"left\"\
+            \n[modes.resize.bindsym]\
+            \n\"Escape\".mode = \"default\"").unwrap()
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(render(&config(), Format::Text),
+                   "[default] Mod4+Return: Open a terminal\
+                   \n[default] Mod4+Left: focus left\
+                   \n[default] Mod4+Shift+q: kill\
+                   \n[resize] Escape: mode default\n");
+    }
+
+    #[test]
+    fn test_variables() {
+        let cfg: Config = toml::from_str("[set]\
+            \nmod = \"Mod4\"\
+            \nterm = \"foot\"\
+            \nmode_system = \"System\"\
+            \n[bindsym]\
+            \n\"$mod+Return\".exec = \"$term\"\
+            \n[modes.\"$mode_system\".bindsym]\
+            \n\"Escape\".mode = \"default\"").unwrap();
+        assert_eq!(render(&cfg, Format::Text),
+                   "[default] Mod4+Return: exec foot\
+                   \n[System] Escape: mode default\n");
+    }
+
+    #[test]
+    fn test_rofi() {
+        assert_eq!(render(&config(), Format::Rofi),
+                   "## exec // Open a terminal // Mod4+Return ##\
+                   \n## focus // focus left // Mod4+Left ##\
+                   \n## kill // kill // Mod4+Shift+q ##\
+                   \n## mode (resize mode) // mode default // Escape ##\n");
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = render(&config(), Format::Markdown);
+        assert!(md.contains("## Mode: default\n\n### exec\n"));
+        assert!(md.contains("| `Mod4+Return` | `exec alacritty` | Open a terminal |"));
+        assert!(md.find("## Mode: default").unwrap() < md.find("## Mode: resize").unwrap());
+    }
+}
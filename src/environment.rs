@@ -0,0 +1,146 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use toml::{Table, Value};
+
+/// Errors that can occur during environment variable interpolation.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EnvError {
+    #[error("Environment variables are not set: {}", .0.join(", "))]
+    Unset(Vec<String>),
+    #[error("Unterminated environment variable reference: {0}")]
+    Unterminated(String),
+}
+
+const PREFIX: &str = "${env:";
+
+/// Replace every `${env:NAME}` reference in the keys and string values of a TOML document.
+///
+/// This is done explicitly (instead of passing `$NAME` through) so that environment variables
+/// can't be confused with Sway variables. If `strict` is set, referencing an unset variable is an
+/// error; otherwise it is replaced with an empty string and a warning is logged.
+pub fn interpolate(doc: &mut Table, env: &HashMap<String, String>, strict: bool) -> Result<(), EnvError> {
+    let mut unset = BTreeSet::new();
+    *doc = interpolate_table(std::mem::take(doc), env, &mut unset)?;
+    if unset.is_empty() { return Ok(()) }
+    let unset: Vec<String> = unset.into_iter().collect();
+    if strict { return Err(EnvError::Unset(unset)) }
+    log::warn!("Environment variables are not set, replacing with empty strings: {}", unset.join(", "));
+    Ok(())
+}
+
+fn interpolate_table(table: Table, env: &HashMap<String, String>, unset: &mut BTreeSet<String>) -> Result<Table, EnvError> {
+    table.into_iter()
+        .map(|(k, v)| Ok((replace(&k, env, unset)?, interpolate_value(v, env, unset)?)))
+        .collect()
+}
+
+fn interpolate_value(value: Value, env: &HashMap<String, String>, unset: &mut BTreeSet<String>) -> Result<Value, EnvError> {
+    Ok(match value {
+        Value::String(s) => Value::String(replace(&s, env, unset)?),
+        Value::Array(a) => Value::Array(a.into_iter()
+            .map(|v| interpolate_value(v, env, unset))
+            .collect::<Result<Vec<Value>, EnvError>>()?),
+        Value::Table(t) => Value::Table(interpolate_table(t, env, unset)?),
+        v => v,
+    })
+}
+
+fn replace(s: &str, env: &HashMap<String, String>, unset: &mut BTreeSet<String>) -> Result<String, EnvError> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find(PREFIX) {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + PREFIX.len()..];
+        let end = after.find('}').ok_or_else(|| EnvError::Unterminated(s.to_string()))?;
+        let name = &after[..end];
+        match env.get(name) {
+            Some(v) => result.push_str(v),
+            None => { unset.insert(name.to_string()); }
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Expand a leading `~` in a path to the user's home directory.
+pub fn expand_tilde(path: &str) -> String {
+    expand_home(path, std::env::var("HOME").ok().as_deref())
+}
+
+/// Expand a leading `~` in a path to `home`. `~user` is left alone, since that would need a
+/// lookup in the password database.
+fn expand_home(path: &str, home: Option<&str>) -> String {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match home {
+            Some(home) => format!("{home}{rest}"),
+            None => {
+                log::warn!("HOME is not set, cannot expand {}", path);
+                path.to_string()
+            }
+        },
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env() -> HashMap<String, String> {
+        HashMap::from([("HOME".to_string(), "/home/me".to_string())])
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let mut doc: Table = toml::from_str("[set]\
+            \nbg_file = \"${env:HOME}/Pictures/mountains.png\"\
+            \nlock = \"$cfg_dir/lock.sh\"").unwrap();
+        interpolate(&mut doc, &env(), true).unwrap();
+        assert_eq!(doc["set"]["bg_file"].as_str(), Some("/home/me/Pictures/mountains.png"));
+        assert_eq!(doc["set"]["lock"].as_str(), Some("$cfg_dir/lock.sh"));
+    }
+
+    #[test]
+    fn test_unset() {
+        let src = "exec = [\"${env:NOPE} ${env:HOME}\"]";
+        let mut doc: Table = toml::from_str(src).unwrap();
+        assert_eq!(interpolate(&mut doc, &env(), true), Err(EnvError::Unset(vec!["NOPE".to_string()])));
+
+        let mut doc: Table = toml::from_str(src).unwrap();
+        interpolate(&mut doc, &env(), false).unwrap();
+        assert_eq!(doc["exec"][0].as_str(), Some(" /home/me"));
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = Some("/home/me");
+        assert_eq!(expand_home("~", home), "/home/me");
+        assert_eq!(expand_home("~/.config/sway/outputs", home), "/home/me/.config/sway/outputs");
+        assert_eq!(expand_home("~other/outputs", home), "~other/outputs");
+        assert_eq!(expand_home("/etc/sway/config.d/*", home), "/etc/sway/config.d/*");
+        assert_eq!(expand_home("~/outputs", None), "~/outputs");
+    }
+
+    #[test]
+    fn test_unterminated() {
+        let mut doc: Table = toml::from_str("exec = [\"${env:HOME\"]").unwrap();
+        assert!(matches!(interpolate(&mut doc, &env(), false), Err(EnvError::Unterminated(_))));
+    }
+}
@@ -0,0 +1,86 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::BTreeSet;
+use thiserror::Error;
+use toml::de::Error as TomlError;
+use crate::environment::{self, EnvError};
+use crate::migrate::{self, MigrateError};
+use crate::profile::{self, Host, ProfileError, Selected};
+use crate::sway::analysis::{self, Conflict};
+use crate::sway::config::Config;
+use crate::sway::vars::{self, Variables, VarError};
+
+/// Errors that can occur while turning a TOML file into a [Config].
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("Config Parse Error: {0}")]
+    Toml(#[from] TomlError),
+    #[error("Profile Error: {0}")]
+    Profile(#[from] ProfileError),
+    #[error("Variable Error: {0}")]
+    Var(#[from] VarError),
+    #[error("Environment Error: {0}")]
+    Env(#[from] EnvError),
+    #[error("Migration Error: {0}")]
+    Migrate(#[from] MigrateError),
+}
+
+/// Options for [load].
+#[derive(Debug, Clone, Default)]
+pub struct LoadOpts {
+    /// The profile to apply, instead of the first one that matches the host
+    pub profile: Option<String>,
+    /// Fail on unset environment variables instead of replacing them with an empty string
+    pub strict_env: bool,
+    /// Inline `set` variables instead of emitting `set` commands
+    pub inline_vars: bool,
+}
+
+/// A loaded config, along with everything worth warning about along the way.
+#[derive(Debug, Clone)]
+pub struct Loaded {
+    pub config: Config,
+    /// The migrations that were applied to bring the file up to date
+    pub migrations: Vec<&'static str>,
+    pub profile: Option<Selected>,
+    /// The resolved `set` variables
+    pub vars: Variables,
+    /// Variables that are referenced, but not defined in `[set]`
+    pub undefined: BTreeSet<String>,
+    /// Bindings that shadow each other or the ones generated by `[autogen]`
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Turn the text of a TOML file into a [Config]: migrate it to the current format, apply a
+/// profile, interpolate environment variables, resolve `set` variables and expand `[autogen]`.
+pub fn load(text: &str, opts: &LoadOpts, host: &Host) -> Result<Loaded, LoadError> {
+    let mut doc: toml_edit::DocumentMut = text.parse().map_err(MigrateError::from)?;
+    let migrations = migrate::migrate(&mut doc)?;
+    let mut doc: toml::Table = toml::from_str(&doc.to_string())?;
+    let profile = profile::apply(&mut doc, opts.profile.as_deref(), host)?;
+    environment::interpolate(&mut doc, &host.env, opts.strict_env)?;
+    let vars = Variables::from_doc(&doc)?;
+    let undefined = vars::undefined(&doc, &vars);
+    if opts.inline_vars {
+        log::info!("Inlining variables");
+        vars::inline(&mut doc, &vars)?;
+    }
+    let mut config: Config = doc.try_into()?;
+    let mut conflicts = config.expand_autogen();
+    conflicts.extend(analysis::binding_conflicts(&config));
+    Ok(Loaded { config, migrations, profile, vars, undefined, conflicts })
+}
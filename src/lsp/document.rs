@@ -0,0 +1,271 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use serde_json::{json, Value};
+use crate::sway::config::BindKind;
+
+/// A key (or table header) in a TOML document, with its full path.
+///
+/// This is found with a line-based scan rather than a real TOML parser, since the TOML parser
+/// doesn't keep track of where keys are. That is good enough for the usual
+/// `key = value` / `[table]` layout of config files.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Entry {
+    /// Full path of the key, including the table it is in
+    pub path: Vec<String>,
+    /// Byte ranges of each path segment. Segments that come from a table header point into the header.
+    pub spans: Vec<(usize, usize)>,
+    /// Byte offset of the `=` sign, if this is a key / value pair (and not a table header)
+    pub equals: Option<usize>,
+}
+
+/// A binding key found in the document.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BindingRef {
+    pub mode: String,
+    pub kind: BindKind,
+    pub keys: String,
+    pub span: (usize, usize),
+}
+
+/// An open text document.
+pub struct Document {
+    pub text: String,
+    line_starts: Vec<usize>,
+}
+
+/// Split a (possibly dotted) TOML key into its segments, along with the byte range of each segment.
+/// `offset` is added to every range.
+fn split_key(key: &str, offset: usize) -> Vec<(String, (usize, usize))> {
+    let mut segments = Vec::new();
+    let mut chars = key.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let mut end = key.len();
+                for (i, d) in chars.by_ref() {
+                    if d == c { end = i; break }
+                }
+                segments.push((key[start + 1..end].to_string(), (offset + start + 1, offset + end)));
+            }
+            '.' => (),
+            c if c.is_whitespace() => (),
+            _ => {
+                let mut end = key.len();
+                while let Some((i, d)) = chars.peek() {
+                    if *d == '.' || d.is_whitespace() { end = *i; break }
+                    chars.next();
+                }
+                segments.push((key[start..end].to_string(), (offset + start, offset + end)));
+            }
+        }
+    }
+    segments
+}
+
+/// Find the first occurrence of `c` in `s` that is not inside a string.
+fn find_unquoted(s: &str, c: char) -> Option<usize> {
+    let mut quote = None;
+    for (i, d) in s.char_indices() {
+        match quote {
+            Some(q) if d == q => quote = None,
+            Some(_) => (),
+            None if d == '"' || d == '\'' => quote = Some(d),
+            None if d == c => return Some(i),
+            None => (),
+        }
+    }
+    None
+}
+
+/// How much deeper a line nests arrays / inline tables, ignoring strings and comments.
+fn depth_change(s: &str) -> i32 {
+    let mut quote = None;
+    let mut depth = 0;
+    for d in s.chars() {
+        match quote {
+            Some(q) if d == q => quote = None,
+            Some(_) => (),
+            None if d == '"' || d == '\'' => quote = Some(d),
+            None if d == '#' => break,
+            None if d == '[' || d == '{' => depth += 1,
+            None if d == ']' || d == '}' => depth -= 1,
+            None => (),
+        }
+    }
+    depth
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Document { text, line_starts }
+    }
+
+    /// Convert a byte offset into an LSP position (zero-based line and UTF-16 column).
+    pub fn position(&self, offset: usize) -> Value {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|s| *s <= offset) - 1;
+        let character = self.text[self.line_starts[line]..offset].encode_utf16().count();
+        json!({ "line": line, "character": character })
+    }
+
+    /// Convert an LSP position into a byte offset.
+    pub fn offset(&self, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+        let Some(start) = self.line_starts.get(line) else { return self.text.len() };
+        let mut units = 0;
+        for (i, c) in self.text[*start..].char_indices() {
+            if units >= character || c == '\n' { return start + i }
+            units += c.len_utf16();
+        }
+        self.text.len()
+    }
+
+    pub fn range(&self, (start, end): (usize, usize)) -> Value {
+        json!({ "start": self.position(start), "end": self.position(end) })
+    }
+
+    /// The word around an offset. Words are made up of alphanumeric characters, `-`, `_` and a
+    /// leading `$` (so variables are a single word).
+    pub fn word_at(&self, offset: usize) -> Option<(usize, usize)> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+        let before = &self.text[..offset.min(self.text.len())];
+        let mut start = before.char_indices().rev()
+            .take_while(|(_, c)| is_word(*c))
+            .last().map(|(i, _)| i).unwrap_or(before.len());
+        let end = self.text[start..].char_indices()
+            .find(|(_, c)| !is_word(*c))
+            .map(|(i, _)| start + i).unwrap_or(self.text.len());
+        if self.text[..start].ends_with('$') { start -= 1 }
+        if start == end { None } else { Some((start, end)) }
+    }
+
+    /// All keys and table headers in the document, in order.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        let mut table: Vec<(String, (usize, usize))> = Vec::new();
+        let mut depth = 0;
+        for (n, line) in self.text.split('\n').enumerate() {
+            let start = self.line_starts[n];
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            // values spanning multiple lines (arrays, mostly) don't contain keys we care about
+            if depth > 0 {
+                depth += depth_change(line);
+                continue
+            }
+            if trimmed.starts_with('#') || trimmed.is_empty() { continue }
+            if trimmed.starts_with('[') {
+                let open = if trimmed.starts_with("[[") { 2 } else { 1 };
+                let close = find_unquoted(&trimmed[open..], ']').map(|i| i + open).unwrap_or(trimmed.len());
+                table = split_key(&trimmed[open..close], start + indent + open);
+                entries.push(Entry {
+                    path: table.iter().map(|(s, _)| s.clone()).collect(),
+                    spans: table.iter().map(|(_, r)| *r).collect(),
+                    equals: None,
+                });
+            } else if let Some(eq) = find_unquoted(trimmed, '=') {
+                let key = split_key(&trimmed[..eq], start + indent);
+                let all: Vec<&(String, (usize, usize))> = table.iter().chain(key.iter()).collect();
+                entries.push(Entry {
+                    path: all.iter().map(|(s, _)| s.clone()).collect(),
+                    spans: all.iter().map(|(_, r)| *r).collect(),
+                    equals: Some(start + indent + eq),
+                });
+                depth += depth_change(&trimmed[eq + 1..]);
+            }
+        }
+        entries
+    }
+
+    /// The entry on the same line as an offset.
+    pub fn entry_at(&self, offset: usize) -> Option<Entry> {
+        let line = self.line_starts.partition_point(|s| *s <= offset) - 1;
+        let line_end = self.line_starts.get(line + 1).copied().unwrap_or(self.text.len());
+        self.entries().into_iter()
+            .find(|e| e.spans.last().is_some_and(|(s, _)| (self.line_starts[line]..line_end).contains(s)))
+    }
+
+    /// All bindings in the document (the first place each binding's keys appear).
+    pub fn bindings(&self) -> Vec<BindingRef> {
+        let mut bindings: Vec<BindingRef> = Vec::new();
+        for entry in self.entries() {
+            let Some((mode, rest)) = binding_path(&entry.path) else { continue };
+            let (kind, keys) = rest;
+            if bindings.iter().any(|b| b.mode == mode && b.kind == kind && b.keys == keys) { continue }
+            let span = entry.spans[entry.path.iter().position(|p| *p == keys).unwrap_or(0)];
+            bindings.push(BindingRef { mode, kind, keys, span });
+        }
+        bindings
+    }
+}
+
+/// If a key path is (inside of) a binding, return its mode, kind and keys.
+pub fn binding_path(path: &[String]) -> Option<(String, (BindKind, String))> {
+    let kind = |s: &str| match s {
+        "bindsym" => Some(BindKind::Sym),
+        "bindcode" => Some(BindKind::Code),
+        _ => None,
+    };
+    match path {
+        [b, keys, ..] if kind(b).is_some() => Some(("default".to_string(), (kind(b)?, keys.clone()))),
+        [m, mode, b, keys, ..] if m == "modes" && kind(b).is_some() => Some((mode.clone(), (kind(b)?, keys.clone()))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "exec = [\
+        \n    \"a = b\",\
+        \n]\
+        \n[set]\
+        \nmod = \"Mod4\"\
+        \n[bindsym]\
+        \n\"$mod+Return\".exec = \"foot\"\
+        \n\"$mod+ö\" = { kill = {} }\
+        \n[modes.resize.bindsym]\
+        \nEscape.mode = \"default\"\n";
+
+    #[test]
+    fn test_entries() {
+        let doc = Document::new(TEXT.to_string());
+        let paths: Vec<String> = doc.entries().iter().map(|e| e.path.join("/")).collect();
+        assert_eq!(paths, vec!["exec", "set", "set/mod", "bindsym", "bindsym/$mod+Return/exec",
+                               "bindsym/$mod+ö", "modes/resize/bindsym", "modes/resize/bindsym/Escape/mode"]);
+        let bindings = doc.bindings();
+        assert_eq!(bindings.len(), 3);
+        assert_eq!(&doc.text[bindings[0].span.0..bindings[0].span.1], "$mod+Return");
+        assert_eq!(bindings[2].mode, "resize");
+        assert_eq!(&doc.text[bindings[2].span.0..bindings[2].span.1], "Escape");
+    }
+
+    #[test]
+    fn test_positions() {
+        let doc = Document::new(TEXT.to_string());
+        let offset = TEXT.find("ö\"").unwrap() + "ö".len();
+        let position = doc.position(offset);
+        assert_eq!(position, json!({ "line": 7, "character": 7 }));
+        assert_eq!(doc.offset(&position), offset);
+        let (start, end) = doc.word_at(TEXT.find("od+Return").unwrap()).unwrap();
+        assert_eq!(&TEXT[start..end], "$mod");
+    }
+}
@@ -0,0 +1,464 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+/// Message framing and JSON-RPC helpers.
+mod rpc;
+/// Open documents, and locating keys in them.
+mod document;
+
+use std::collections::HashMap;
+use std::io::{BufRead, Error as IoError, Write};
+use serde_json::{json, Value};
+use crate::load::{self, LoadError, LoadOpts};
+use crate::migrate::MigrateError;
+use crate::profile::Host;
+use crate::schema;
+use crate::sway::analysis::MODIFIERS;
+use crate::sway::config::Config;
+use crate::sway::vars::Variables;
+use document::{binding_path, Document};
+
+const ERROR: u8 = 1;
+const WARNING: u8 = 2;
+
+/// Keysyms offered when completing binding keys (besides letters, digits and function keys).
+const KEYSYMS: [&str; 37] = [
+    "Return", "Escape", "Tab", "space", "BackSpace", "Delete", "Insert", "Home", "End", "Prior",
+    "Next", "Left", "Right", "Up", "Down", "Print", "minus", "equal", "comma", "period", "slash",
+    "semicolon", "apostrophe", "bracketleft", "bracketright", "backslash", "grave",
+    "XF86AudioRaiseVolume", "XF86AudioLowerVolume", "XF86AudioMute", "XF86AudioMicMute",
+    "XF86AudioPlay", "XF86AudioPause", "XF86AudioNext", "XF86AudioPrev", "XF86MonBrightnessUp",
+    "XF86MonBrightnessDown",
+];
+
+/// Documentation for every key in the TOML format, taken from the JSON schema (which in turn is
+/// taken from the doc comments on [Config], [Runtime](crate::sway::runtime::Runtime), etc.).
+fn key_docs(schema: &Value) -> HashMap<String, String> {
+    fn walk(value: &Value, docs: &mut HashMap<String, String>) {
+        match value {
+            Value::Object(obj) => {
+                // enum variants: `{ "required": ["name"], "description": ... }` or `{ "const": "name", ... }`
+                if let Some(Value::String(desc)) = obj.get("description") {
+                    let name = match (obj.get("const"), obj.get("required")) {
+                        (Some(Value::String(c)), _) => Some(c.clone()),
+                        (_, Some(Value::Array(r))) if r.len() == 1 => r[0].as_str().map(|s| s.to_string()),
+                        _ => None,
+                    };
+                    if let Some(name) = name { docs.entry(name).or_insert_with(|| desc.clone()); }
+                }
+                if let Some(Value::Object(props)) = obj.get("properties") {
+                    for (name, prop) in props {
+                        if let Some(Value::String(desc)) = prop.get("description") {
+                            docs.entry(name.clone()).or_insert_with(|| desc.clone());
+                        }
+                    }
+                }
+                obj.values().for_each(|v| walk(v, docs));
+            }
+            Value::Array(arr) => arr.iter().for_each(|v| walk(v, docs)),
+            _ => (),
+        }
+    }
+    let mut docs = HashMap::new();
+    // command docs take priority over fields that happen to have the same name
+    walk(&schema["$defs"]["Runtime"], &mut docs);
+    walk(schema, &mut docs);
+    docs
+}
+
+/// A language server for swayconf TOML files.
+pub struct Server {
+    documents: HashMap<String, Document>,
+    docs: HashMap<String, String>,
+    schema: Value,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        let schema = schema::generate().to_value();
+        Server { documents: HashMap::new(), docs: key_docs(&schema), schema }
+    }
+
+    /// Handle a single incoming message, and return the messages to send back.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        let Some(id) = message.get("id") else {
+            // notifications
+            return match method {
+                "textDocument/didOpen" => {
+                    let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                    self.documents.insert(uri.clone(), Document::new(text.to_string()));
+                    vec![self.diagnostics(&uri)]
+                }
+                "textDocument/didChange" => {
+                    // full sync, so the last change has the whole document
+                    let Some(text) = params["contentChanges"].as_array().and_then(|c| c.last()) else { return vec![] };
+                    let text = text["text"].as_str().unwrap_or_default();
+                    self.documents.insert(uri.clone(), Document::new(text.to_string()));
+                    vec![self.diagnostics(&uri)]
+                }
+                "textDocument/didClose" => {
+                    self.documents.remove(&uri);
+                    vec![rpc::notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))]
+                }
+                _ => vec![],
+            }
+        };
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": { "triggerCharacters": ["+", "$", "\""] },
+                    "codeLensProvider": { "resolveProvider": false },
+                },
+                "serverInfo": { "name": "swayconf", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/hover" => self.with_document(&uri, params, |s, doc, offset| s.hover(doc, offset)),
+            "textDocument/definition" => self.with_document(&uri, params, |_, doc, offset| definition(&uri, doc, offset)),
+            "textDocument/completion" => self.with_document(&uri, params, completion),
+            "textDocument/codeLens" => match self.documents.get(&uri) {
+                Some(doc) => code_lenses(doc),
+                None => Value::Null,
+            },
+            _ => return vec![rpc::error(id, rpc::METHOD_NOT_FOUND, &format!("Unknown method {method}"))],
+        };
+        vec![rpc::response(id, result)]
+    }
+
+    fn with_document(&self, uri: &str, params: &Value, f: impl Fn(&Self, &Document, usize) -> Value) -> Value {
+        match self.documents.get(uri) {
+            Some(doc) => f(self, doc, doc.offset(&params["position"])),
+            None => Value::Null,
+        }
+    }
+
+    fn diagnostics(&self, uri: &str) -> Value {
+        let diagnostics = match self.documents.get(uri) {
+            Some(doc) => diagnostics(doc),
+            None => vec![],
+        };
+        rpc::notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+
+    fn hover(&self, doc: &Document, offset: usize) -> Value {
+        let Some((start, end)) = doc.word_at(offset) else { return Value::Null };
+        let word = &doc.text[start..end];
+        let contents = match word.strip_prefix('$') {
+            Some(name) => {
+                match variables(doc).and_then(|v| v.get(name).cloned()) {
+                    Some(value) => format!("`${name}` = `{value}`"),
+                    None => return Value::Null,
+                }
+            }
+            None => match self.docs.get(word) {
+                Some(desc) => format!("**{word}**\n\n{desc}"),
+                None => return Value::Null,
+            },
+        };
+        json!({ "contents": { "kind": "markdown", "value": contents }, "range": doc.range((start, end)) })
+    }
+}
+
+/// Find where a key is defined (used to attach diagnostics to something more useful than the first
+/// line).
+fn key_span(doc: &Document, path: &[&str]) -> (usize, usize) {
+    doc.entries().iter()
+        .find(|e| e.path.len() >= path.len() && e.path.iter().zip(path).all(|(a, b)| a == b))
+        .map(|e| e.spans[path.len() - 1])
+        .unwrap_or((0, 0))
+}
+
+fn diagnostic(doc: &Document, span: (usize, usize), severity: u8, message: String) -> Value {
+    json!({ "range": doc.range(span), "severity": severity, "source": "swayconf", "message": message })
+}
+
+/// Where in the document the cause of a load error is.
+fn error_span(doc: &Document, err: &LoadError) -> (usize, usize) {
+    match err {
+        LoadError::Migrate(MigrateError::Parse(e)) => e.span().map(|r| (r.start, r.end)).unwrap_or((0, 0)),
+        LoadError::Migrate(_) => key_span(doc, &["version"]),
+        LoadError::Profile(_) => key_span(doc, &["profile"]),
+        LoadError::Var(_) => key_span(doc, &["set"]),
+        // the converted document can differ from the text (after migrating or applying a profile),
+        // so only trust the location from parsing the text directly if it is the same error
+        LoadError::Toml(e) => match toml::from_str::<Config>(&doc.text) {
+            Err(raw) if raw.message() == e.message() => raw.span().map(|r| (r.start, r.end)).unwrap_or((0, 0)),
+            _ => (0, 0),
+        },
+        LoadError::Env(_) => (0, 0),
+    }
+}
+
+/// Run the same checks as a conversion does, and report every problem found.
+fn diagnostics(doc: &Document) -> Vec<Value> {
+    let loaded = match load::load(&doc.text, &LoadOpts::default(), &Host::current()) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            let message = match &e {
+                LoadError::Toml(e) => e.message().to_string(),
+                LoadError::Migrate(MigrateError::Parse(e)) => e.message().to_string(),
+                e => e.to_string(),
+            };
+            return vec![diagnostic(doc, error_span(doc, &e), ERROR, message)]
+        }
+    };
+    let mut diagnostics = Vec::new();
+    for m in &loaded.migrations {
+        diagnostics.push(diagnostic(doc, key_span(doc, &["version"]), WARNING,
+                                    format!("Outdated config format, run `swayconf migrate` to upgrade: {m}")));
+    }
+    for (start, end, name) in loaded.vars.undefined_spans(&doc.text) {
+        if loaded.undefined.contains(&name) {
+            diagnostics.push(diagnostic(doc, (start, end), WARNING, format!("Undefined variable ${name}")));
+        }
+    }
+    let bindings = doc.bindings();
+    for conflict in &loaded.conflicts {
+        for keys in &conflict.keys {
+            let found = bindings.iter()
+                .find(|b| b.mode == conflict.mode && b.kind == conflict.bind && b.keys == *keys);
+            if let Some(b) = found {
+                diagnostics.push(diagnostic(doc, b.span, WARNING, conflict.to_string()));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// The `set` variables of a document, resolved the same way a conversion does.
+fn variables(doc: &Document) -> Option<Variables> {
+    load::load(&doc.text, &LoadOpts::default(), &Host::current()).ok().map(|l| l.vars)
+}
+
+fn location(uri: &str, doc: &Document, span: (usize, usize)) -> Value {
+    json!({ "uri": uri, "range": doc.range(span) })
+}
+
+/// Go to the definition of a `$variable` (in the `[set]` table) or a mode (in the `[modes]` table).
+fn definition(uri: &str, doc: &Document, offset: usize) -> Value {
+    let Some((start, end)) = doc.word_at(offset) else { return Value::Null };
+    let word = &doc.text[start..end];
+    let entries = doc.entries();
+    let target = match word.strip_prefix('$') {
+        Some(name) => entries.iter().find(|e| e.path.len() == 2 && e.path[0] == "set" && e.path[1] == name),
+        None => entries.iter().find(|e| e.path.len() >= 2 && e.path[0] == "modes" && e.path[1] == word),
+    };
+    match target {
+        Some(e) if e.spans[e.path.len().min(2) - 1] != (start, end) => location(uri, doc, e.spans[1]),
+        _ => Value::Null,
+    }
+}
+
+fn completion(server: &Server, doc: &Document, offset: usize) -> Value {
+    let item = |label: &str, kind: u8, detail: Option<&String>| json!({ "label": label, "kind": kind, "detail": detail });
+    let entry = doc.entry_at(offset);
+    let in_key = entry.as_ref().is_none_or(|e| e.equals.is_none_or(|eq| offset <= eq));
+    // the table we're in applies even if the key hasn't been written yet
+    let path = match &entry {
+        Some(e) => e.path.clone(),
+        None => doc.entries().into_iter().rev()
+            .find(|e| e.equals.is_none() && e.spans[0].0 < offset)
+            .map(|e| { let mut p = e.path; p.push(String::new()); p })
+            .unwrap_or_default(),
+    };
+    let mut items = Vec::new();
+    if binding_path(&path).is_some() && in_key {
+        if let Some(vars) = variables(doc) {
+            items.extend(vars.order().iter().map(|v| item(&format!("${v}"), 6, vars.get(v))));
+        }
+        items.extend(MODIFIERS.iter().map(|(m, _)| item(m, 14, None)));
+        let generated: Vec<String> = ('a'..='z').chain('0'..='9').map(|c| c.to_string())
+            .chain((1..=12).map(|n| format!("F{n}")))
+            .collect();
+        items.extend(generated.iter().map(|k| item(k, 21, None)));
+        items.extend(KEYSYMS.iter().map(|k| item(k, 21, None)));
+    } else if binding_path(&path).is_some() {
+        if let Some(variants) = server.schema["$defs"]["Runtime"]["oneOf"].as_array() {
+            let mut names: Vec<&str> = variants.iter()
+                .filter_map(|v| v["required"][0].as_str().or(v["const"].as_str()))
+                .collect();
+            names.sort();
+            names.dedup();
+            items.extend(names.iter().map(|n| item(n, 3, server.docs.get(*n))));
+        }
+    }
+    Value::Array(items)
+}
+
+/// Show the generated Sway command above every binding.
+fn code_lenses(doc: &Document) -> Value {
+    let Ok(loaded) = load::load(&doc.text, &LoadOpts::default(), &Host::current()) else { return json!([]) };
+    let cfg = loaded.config;
+    let refs = doc.bindings();
+    let lenses: Vec<Value> = cfg.bindings().iter()
+        .filter_map(|b| {
+            let r = refs.iter().find(|r| r.mode == b.mode && r.kind == b.kind && r.keys == b.keys)?;
+            let line = b.to_string();
+            Some(json!({
+                "range": doc.range(r.span),
+                "command": { "title": line, "command": "swayconf.showGenerated", "arguments": [line] },
+            }))
+        })
+        .collect();
+    Value::Array(lenses)
+}
+
+/// Run the language server over a reader / writer pair (normally stdin and stdout) until the client
+/// sends an `exit` notification or closes the input.
+pub fn run(mut reader: impl BufRead, mut writer: impl Write) -> Result<(), IoError> {
+    let mut server = Server::new();
+    while let Some(message) = rpc::read_message(&mut reader)? {
+        if message["method"] == "exit" { break }
+        for reply in server.handle(&message) {
+            rpc::write_message(&mut writer, &reply)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const URI: &str = "file:///home/me/.config/sway/config.toml";
+    const TEXT: &str = "[set]\
+        \nmod = \"Mod4\"\
+        \n[bindsym]\
+        \n\"$mod+Return\".exec = \"foot\"\
+        \n\"$mod+r\".mode = \"resize\"\
+        \n\"Mod4+return\".kill = {}\
+        \n[modes.resize.bindsym]\
+        \nEscape.mode = \"default\"\n";
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn at(text: &str, needle: &str) -> Value {
+        Document::new(text.to_string()).position(text.find(needle).unwrap())
+    }
+
+    /// Send messages through [run], as an editor would over stdio, and collect the replies.
+    fn session(messages: Vec<Value>) -> Vec<Value> {
+        let mut input = Vec::new();
+        for m in messages.iter().chain([&json!({ "jsonrpc": "2.0", "method": "exit" })]) {
+            rpc::write_message(&mut input, m).unwrap();
+        }
+        let mut output = Vec::new();
+        run(Cursor::new(input), &mut output).unwrap();
+        let mut reader = Cursor::new(output);
+        let mut replies = Vec::new();
+        while let Some(m) = rpc::read_message(&mut reader).unwrap() { replies.push(m) }
+        replies
+    }
+
+    fn open(text: &str) -> Value {
+        rpc::notification("textDocument/didOpen", json!({ "textDocument": { "uri": URI, "text": text } }))
+    }
+
+    fn params(position: Value) -> Value {
+        json!({ "textDocument": { "uri": URI }, "position": position })
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let replies = session(vec![
+            request(1, "initialize", json!({})),
+            open(TEXT),
+            open("[bindsym]\n\"Mod4+x\".exce = \"foot\"\n"),
+        ]);
+        assert_eq!(replies[0]["result"]["capabilities"]["hoverProvider"], true);
+        // the two bindings that only differ by case
+        let diagnostics = replies[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["severity"], WARNING);
+        assert_eq!(diagnostics[1]["range"]["start"], at(TEXT, "Mod4+return"));
+        // the misspelled command
+        let diagnostics = replies[2]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics[0]["severity"], ERROR);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+    }
+
+    #[test]
+    fn test_undefined_diagnostics() {
+        let text = "[set]\
+            \nmode_system = \"System\"\
+            \n[bindsym]\
+            \n\"$mod+q\".mode = \"$mode_system\"\
+            \n\"$mod+Shift+q\".mode = \"$mode_system\"\n";
+        let replies = session(vec![open(text)]);
+        // `$mode_system` is defined, so only the `$mod`s are undefined
+        let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["message"], "Undefined variable $mod");
+        assert_eq!(diagnostics[0]["range"]["start"], at(text, "$mod+q"));
+        assert_eq!(diagnostics[1]["range"]["start"], at(text, "$mod+Shift"));
+    }
+
+    #[test]
+    fn test_outdated_diagnostics() {
+        let text = "version = \"0.2\"\
+            \n[bindsym]\
+            \n\"Mod4+a\".criteria-focus = [{ con-mark = \"a\" }]\
+            \n\"Mod4+b\".exce = \"foot\"\n";
+        let replies = session(vec![open(text), open(&text.replace("exce", "exec"))]);
+        // the older format is upgraded like the CLI does, so only the real mistake is an error
+        let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], ERROR);
+        let diagnostics = replies[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], WARNING);
+        assert_eq!(diagnostics[0]["range"]["start"], at(text, "version"));
+    }
+
+    #[test]
+    fn test_requests() {
+        let replies = session(vec![
+            open(TEXT),
+            request(1, "textDocument/hover", params(at(TEXT, "exec"))),
+            request(2, "textDocument/hover", params(at(TEXT, "od+Return"))),
+            request(3, "textDocument/definition", params(at(TEXT, "od+Return"))),
+            request(4, "textDocument/definition", params(at(TEXT, "resize\"\n"))),
+            request(5, "textDocument/completion", params(at(TEXT, "Return\".exec"))),
+            request(6, "textDocument/codeLens", json!({ "textDocument": { "uri": URI } })),
+            request(7, "textDocument/formatting", json!({})),
+        ]);
+        assert!(replies[1]["result"]["contents"]["value"].as_str().unwrap().starts_with("**exec**\n\nRun a shell command"));
+        assert_eq!(replies[2]["result"]["contents"]["value"], "`$mod` = `Mod4`");
+        assert_eq!(replies[3]["result"]["range"]["start"], at(TEXT, "mod ="));
+        assert_eq!(replies[4]["result"]["range"]["start"], at(TEXT, "resize.bindsym"));
+        let completions = replies[5]["result"].as_array().unwrap();
+        assert!(completions.iter().any(|c| c["label"] == "$mod"));
+        assert!(completions.iter().any(|c| c["label"] == "XF86AudioMute"));
+        let lenses = replies[6]["result"].as_array().unwrap();
+        assert!(lenses.iter().any(|l| l["command"]["title"] == "bindsym $mod+Return exec foot"));
+        assert!(lenses.iter().any(|l| l["command"]["title"] == "bindsym Escape mode default"));
+        assert_eq!(replies[7]["error"]["code"], rpc::METHOD_NOT_FOUND);
+    }
+}
@@ -45,102 +45,400 @@
 /// 
 /// Full documentation on the actual effects of these commands is available in the sway(5) manpage.
 mod sway;
+/// Environment variable interpolation (`${env:NAME}`) and `~` expansion.
+mod environment;
+/// Per-host / per-profile configuration overlays.
+///
+/// Profiles are defined under `[profile.<name>]` and are merged over the base configuration before
+/// it is parsed. See [profile::apply] for how a profile is selected.
+mod profile;
+/// Key binding cheat sheet generation.
+mod cheatsheet;
+/// Applying parts of a config to a running Sway instance over IPC.
+mod apply;
+/// Watching files for changes.
+mod watch;
+/// Safely replacing generated config files (validation, backups and rollback).
+mod writer;
+/// JSON Schema generation for the TOML format.
+mod schema;
+/// Language server for swayconf TOML files.
+mod lsp;
+/// Versioning of the TOML format, and migrations from older versions.
+mod migrate;
+/// Turning a TOML file into a [Config](sway::config::Config), shared by the CLI and the language
+/// server.
+mod load;
+/// Helpers shared by the unit tests.
+#[cfg(test)]
+mod test_util;
 
 use std::fs;
-use std::fs::File;
-use std::io::{Error as IoError, Write};
-use toml::de::Error as TomlError;
-use std::path::{PathBuf};
-use std::process::Command;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use sway::config::Config;
+use sway::ipc::{Connection, IpcError};
+use sway::parse::{self, Statements};
+use profile::Host;
+use migrate::MigrateError;
+use load::{LoadError, LoadOpts};
+use watch::Watcher;
+use writer::{WriteError, WriteOpts};
 use derive_more::{From};
-use clap::Parser;
-use clio::{InputPath, OutputPath, ClioPath};
+use clap::{Parser, Subcommand};
+use clio::{InputPath, OutputPath};
 
 /// Configuration generator for the Sway window manager.
 #[derive(Parser)]
 #[command(version, about)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
     /// The TOML file to read from. Defaults to "./config.toml" if unspecified.
-    #[arg(short, long, value_parser, default_value = "./config.toml")]
-    input_file: InputPath,
+    #[arg(short, long, value_parser, global = true)]
+    input_file: Option<InputPath>,
     /// The location to output the Sway config file to. If unspecified, uses the same path as the
     /// input file, but with the ".toml" extension stripped.
-    #[arg(short, long, value_parser)]
+    #[arg(short, long, value_parser, global = true)]
     output_file: Option<OutputPath>,
     /// Reload Sway if enabled. This can be used if you are writing directly to your Sway config
     /// files.
-    #[arg(short, long, default_value = "false")]
+    #[arg(short, long, default_value = "false", global = true)]
     reload: bool,
+    /// Check the generated config with `sway --validate` before replacing the existing one.
+    #[arg(long, default_value = "false")]
+    validate: bool,
+    /// Don't write anything; instead compare the generated config to the existing output file,
+    /// print a unified diff, and exit with a non-zero status if they differ.
+    #[arg(long, alias = "diff", default_value = "false", conflicts_with_all = ["watch", "reload"])]
+    check: bool,
+    /// The Sway binary used by `--validate`.
+    #[arg(long, default_value = "sway")]
+    sway_binary: PathBuf,
+    /// Keep running, and regenerate the config whenever the input file (or any included file)
+    /// changes. If the new config can't be parsed, the previously generated config is kept.
+    #[arg(short, long, default_value = "false")]
+    watch: bool,
+    /// The profile to apply. If unspecified, the first profile whose `match` rules fit this
+    /// machine's hostname / environment is used.
+    #[arg(short, long, global = true)]
+    profile: Option<String>,
+    /// Inline all `set` variables into the generated commands instead of emitting `set` commands.
+    #[arg(long, default_value = "false", global = true)]
+    inline_vars: bool,
+    /// Fail if a `${env:NAME}` reference names an environment variable that is not set, instead
+    /// of replacing it with an empty string.
+    #[arg(long, default_value = "false", global = true)]
+    strict_env: bool,
+}
+
+/// Additional commands. If none is given, the Sway config is generated.
+#[derive(Subcommand)]
+enum Commands {
+    /// Print a cheat sheet of all key bindings.
+    Cheatsheet {
+        /// The format to print the cheat sheet in.
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: cheatsheet::Format,
+    },
+    /// Send parts of the config to the running Sway instance without reloading it (so exec-always
+    /// commands are not run again).
+    Apply {
+        /// The sections to apply.
+        #[arg(short, long, value_enum, num_args = 1.., required = true)]
+        section: Vec<apply::Section>,
+        /// Print the commands instead of sending them.
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+    /// Compare the generated config with the config Sway is currently running (or another config
+    /// file), to find bindings and other statements that were changed outside of swayconf.
+    ///
+    /// Both configs are parsed first: variables are substituted, key combinations normalized and
+    /// commands brought into a canonical form, so equivalent spellings (such as `yes` and
+    /// `enable`, or `move window` and `move container`) are not reported. Settings are compared
+    /// by name, with the last statement for a setting winning like in Sway.
+    ///
+    /// Lines starting with `-` are only in the generated config, lines starting with `+` are only
+    /// in the live config, and lines starting with `~` are in both with different values.
+    Drift {
+        /// Compare with this Sway config file instead of asking Sway over IPC.
+        #[arg(long, value_parser)]
+        from: Option<InputPath>,
+    },
+    /// Print a JSON Schema for the TOML format, for editor completion and validation (with taplo
+    /// or Even Better TOML, for example). This doesn't read the input file.
+    Schema,
+    /// Run a language server (over stdio) for swayconf TOML files, providing diagnostics, hover
+    /// docs, go-to-definition, completion and code lenses to editors.
+    Lsp,
+    /// Upgrade the input file to the current version of the TOML format (keeping comments and
+    /// formatting). The previous version is backed up.
+    Migrate {
+        /// Print the upgraded file instead of writing it.
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+    /// Restore the previous version of the generated config from its most recent backup.
+    Rollback,
 }
 
 #[derive(Debug, Error, From)]
 enum SwayconfError {
     #[error("I/O Error: {0}")]
     Io(IoError),
-    #[error("Config Parse Error: {0}")]
-    Toml(TomlError),
+    #[error(transparent)]
+    Load(LoadError),
+    #[error("Write Error: {0}")]
+    Write(WriteError),
+    #[error("IPC Error: {0}")]
+    Ipc(IpcError),
+    #[error("Migration Error: {0}")]
+    Migrate(MigrateError),
 }
 
-fn convert(path: &PathBuf) -> Result<Config, SwayconfError> {
+fn convert(path: &Path, args: &Args) -> Result<Config, SwayconfError> {
     log::info!("Opening file {}", path.display());
     let str = fs::read_to_string(path)?;
     log::info!("Parsing configuration: {}", path.display());
-    let cfg: Config = toml::from_str(&str)?;
+    let opts = LoadOpts {
+        profile: args.profile.clone(),
+        strict_env: args.strict_env,
+        inline_vars: args.inline_vars,
+    };
+    let loaded = load::load(&str, &opts, &Host::current())?;
+    for m in &loaded.migrations {
+        log::warn!("Outdated config format, upgrading automatically: {}", m);
+    }
+    if !loaded.migrations.is_empty() {
+        log::info!("Run `swayconf migrate` to upgrade {}", path.display());
+    }
+    if let Some(selected) = &loaded.profile {
+        eprintln!("Using profile {}", selected);
+    }
+    for name in &loaded.undefined {
+        log::warn!("Undefined variable ${}", name);
+    }
+    for conflict in &loaded.conflicts {
+        log::warn!("{}", conflict);
+    }
     log::debug!("Everything went okay, continuing");
-    Ok(cfg)
+    Ok(loaded.config)
 }
 
-fn write(path: &PathBuf, cfg: Config) -> Result<usize, IoError> {
+fn write(path: &Path, args: &Args, cfg: Config) -> Result<(), SwayconfError> {
     log::info!("Writing to file {}", path.display());
-    let mut file = File::create(path.clone())?;
-    file.write(cfg.to_string().as_bytes())
+    let opts = WriteOpts {
+        validate_with: if args.validate { Some(args.sway_binary.clone()) } else { None },
+    };
+    if let Some(backup) = writer::write(path, &cfg.to_string(), &opts)? {
+        log::info!("Backed up the previous config to {}", backup.display());
+    }
+    Ok(())
 }
 
 fn reload_sway() {
-    log::info!("Attempting to reload config via swaymsg...");
-    match Command::new("swaymsg").arg("reload").output() {
-        Ok(output) => {
-            log::debug!("{}", std::str::from_utf8(&*output.stdout).unwrap_or("Error reading stdout"));
-            if output.status.success() {
-                log::debug!("{}", std::str::from_utf8(&*output.stderr).unwrap_or("Error reading stderr"));
-                log::info!("swaymsg exited with status {}", output.status);
-            } else {
-                log::error!("swaymsg exited with status {}", output.status);
-                log::error!("Output of stderr:");
-                log::error!("{}", std::str::from_utf8(&*output.stderr).unwrap_or("Error reading stderr"));
+    log::info!("Attempting to reload config via IPC...");
+    match Connection::connect().and_then(|mut conn| conn.run_command("reload")) {
+        Ok(results) => {
+            for result in results {
+                match result.error {
+                    None => log::info!("Sway reloaded successfully"),
+                    Some(e) => log::error!("Sway failed to reload: {}", e),
+                }
             }
         }
-        Err(err) => {log::error!("swaymsg call failed: {}", err);}
+        Err(err) => log::error!("Failed to reload Sway: {}", err),
     }
 }
 
-/// Main entrypoint
-fn main() {
-    env_logger::init();
+fn output_path(path: &Path, args: &Args) -> PathBuf {
+    match &args.output_file {
+        Some(p) => p.path().to_path_buf(),
+        None => path.with_extension("")
+    }
+}
 
-    let args = Args::parse();
+fn generate(path: &Path, args: &Args, cfg: Config) -> Result<(), SwayconfError> {
+    let write_path = output_path(path, args);
+    write(&write_path, args, cfg)?;
+    log::info!("Successfully wrote to {}", &write_path.display());
+    if args.reload { reload_sway() }
+    Ok(())
+}
 
-    let path = args.input_file.path().to_path_buf();
-    match convert(&path) {
+fn apply_sections(cfg: &Config, sections: &[apply::Section], dry_run: bool) {
+    let commands = apply::commands(cfg, sections);
+    if dry_run {
+        commands.iter().for_each(|c| println!("{c}"));
+        return
+    }
+    let results = Connection::connect().and_then(|mut conn| apply::send(&mut conn, &commands));
+    match results {
+        Ok(results) => {
+            let mut rejected = 0;
+            for (command, result) in results {
+                match result.error {
+                    None if result.success => println!("OK: {command}"),
+                    e => {
+                        rejected += 1;
+                        println!("Rejected: {command} ({})", e.unwrap_or("unknown error".to_string()));
+                    }
+                }
+            }
+            if rejected > 0 {
+                log::error!("Sway rejected {} command(s)", rejected);
+                std::process::exit(1);
+            }
+        }
+        Err(err) => {
+            log::error!("Failed to apply config: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn check(path: &Path, args: &Args, cfg: Config) {
+    let write_path = output_path(path, args);
+    match writer::diff(&write_path, &cfg.to_string()) {
+        Ok(None) => eprintln!("{} is up to date", write_path.display()),
+        Ok(Some(diff)) => {
+            print!("{diff}");
+            eprintln!("{} is out of date", write_path.display());
+            std::process::exit(1);
+        }
+        Err(err) => {
+            log::error!("Failed to read {}: {}", write_path.display(), err);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn drift(cfg: &Config, from: &Option<InputPath>) {
+    let live = match from {
+        Some(p) => fs::read_to_string(p.path().path()).map_err(SwayconfError::from),
+        None => Connection::connect().and_then(|mut conn| conn.get_config()).map_err(SwayconfError::from),
+    };
+    let live = match live {
+        Ok(l) => Statements::parse(&l),
+        Err(err) => {
+            log::error!("Failed to read the live config: {}", err);
+            std::process::exit(2);
+        }
+    };
+    let differences = parse::compare(&Statements::parse(&cfg.to_string()), &live);
+    if differences.is_empty() {
+        eprintln!("The live config matches the generated config");
+        return
+    }
+    differences.iter().for_each(|d| println!("{d}"));
+    std::process::exit(1);
+}
+
+fn migrate_file(path: &Path, dry_run: bool) -> Result<(), SwayconfError> {
+    let mut doc: toml_edit::DocumentMut = fs::read_to_string(path)?.parse().map_err(MigrateError::from)?;
+    let from = migrate::version(&doc)?;
+    let migrations = migrate::migrate(&mut doc)?;
+    if dry_run {
+        print!("{}", doc);
+        return Ok(())
+    }
+    if from == migrate::CURRENT {
+        eprintln!("{} is already up to date (version {})", path.display(), from);
+        return Ok(())
+    }
+    migrations.iter().for_each(|m| eprintln!("Migrated: {m}"));
+    if let Some(backup) = writer::write(path, &doc.to_string(), &WriteOpts::default())? {
+        eprintln!("Upgraded {} to version {} (the previous version is in {})", path.display(), migrate::CURRENT, backup.display());
+    }
+    Ok(())
+}
+
+fn rollback(path: &Path, args: &Args) {
+    let write_path = output_path(path, args);
+    match writer::rollback(&write_path) {
+        Ok(backup) => {
+            eprintln!("Restored {} from {}", write_path.display(), backup.display());
+            if args.reload { reload_sway() }
+        }
+        Err(err) => {
+            log::error!("Failed to roll back {}: {}", write_path.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn watch(path: &Path, args: &Args) {
+    let mut watcher = Watcher::new(vec![path.to_path_buf()], vec![output_path(path, args)]);
+    loop {
+        match convert(path, args) {
+            Ok(cfg) => {
+                log::info!("Successfully converted {}", &path.display());
+                watcher.set_paths(std::iter::once(path.to_path_buf()).chain(cfg.includes()).collect());
+                if let Err(err) = generate(path, args, cfg) {
+                    log::error!("Failed to write to {}: {}", output_path(path, args).display(), err);
+                }
+            }
+            Err(err) => log::error!("Failed to convert {}, keeping the previous config: {}", &path.display(), err),
+        }
+        eprintln!("Watching {} for changes...", path.display());
+        watcher.wait();
+    }
+}
+
+/// Converts the input file for the subcommands that need a config, exiting if that fails.
+fn load(path: &Path, args: &Args) -> Config {
+    match convert(path, args) {
         Ok(cfg) => {
             log::info!("Successfully converted {}", &path.display());
             log::trace!("{:#?}", &cfg);
-            let write_path = match args.output_file {
-                Some(p) => p.path().to_path_buf(),
-                None => path.with_extension("")
-            };
-            match write(&write_path, cfg) {
-                Ok(_) => {
-                    log::info!("Successfully wrote to {}", &write_path.display());
-                    if args.reload { reload_sway() }
-                },
-                Err(e) => log::error!("Failed to write to {}: {}", &write_path.display(), e),
-            }
+            cfg
         }
         Err(err) => {
             log::error!("Failed to convert {}: {}", &path.display(), err);
+            // CI jobs using --check need to tell a broken config apart from an outdated one
+            std::process::exit(if args.check { 2 } else { 1 });
         }
+    }
+}
+
+/// Main entrypoint
+fn main() {
+    // show warnings (such as binding conflicts) unless RUST_LOG says otherwise
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let args = Args::parse();
+
+    let path = match &args.input_file {
+        Some(p) => p.path().to_path_buf(),
+        None => PathBuf::from("./config.toml"),
     };
+    match args.command {
+        Some(Commands::Rollback) => rollback(&path, &args),
+        Some(Commands::Schema) => println!("{}", schema::render()),
+        Some(Commands::Migrate { dry_run }) => {
+            if let Err(err) = migrate_file(&path, dry_run) {
+                log::error!("Failed to migrate {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Lsp) => {
+            if let Err(err) = lsp::run(std::io::stdin().lock(), std::io::stdout().lock()) {
+                log::error!("Language server failed: {}", err);
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Cheatsheet { format }) => print!("{}", cheatsheet::render(&load(&path, &args), format)),
+        Some(Commands::Apply { ref section, dry_run }) => apply_sections(&load(&path, &args), section, dry_run),
+        Some(Commands::Drift { ref from }) => drift(&load(&path, &args), from),
+        None if args.watch => watch(&path, &args),
+        None if args.check => check(&path, &args, load(&path, &args)),
+        None => {
+            if let Err(err) = generate(&path, &args, load(&path, &args)) {
+                log::error!("Failed to write to {}: {}", output_path(&path, &args).display(), err);
+                std::process::exit(1);
+            }
+        }
+    }
 }
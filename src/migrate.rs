@@ -0,0 +1,368 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+use thiserror::Error;
+use toml_edit::{DocumentMut, InlineTable, Item, Key, Table, TableLike, Value};
+
+#[derive(Debug, Error)]
+pub enum MigrateError {
+    #[error("Invalid config version \"{0}\" (expected something like \"{CURRENT}\")")]
+    Invalid(String),
+    #[error("Config version {0} is newer than this version of swayconf supports ({CURRENT}), please upgrade swayconf")]
+    Unsupported(Version),
+    #[error("{0}")]
+    Parse(#[from] toml_edit::TomlError),
+}
+
+/// Version of the TOML format (the top-level `version` key), as `MAJOR.MINOR`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+pub struct Version(u32, u32);
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}.{}", self.0, self.1)
+    }
+}
+
+impl FromStr for Version {
+    type Err = MigrateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MigrateError::Invalid(s.to_string());
+        let (major, minor) = s.split_once('.').ok_or_else(invalid)?;
+        Ok(Version(major.parse().map_err(|_| invalid())?, minor.parse().map_err(|_| invalid())?))
+    }
+}
+
+/// The current version of the TOML format.
+pub const CURRENT: Version = Version(0, 3);
+
+/// The version of documents without a `version` key, which were written before it existed.
+pub const OLDEST: Version = Version(0, 1);
+
+/// An upgrade from one version of the TOML format to the next.
+struct Migration {
+    /// The version this migration upgrades from (to the version of the next migration, or [CURRENT])
+    from: Version,
+    description: &'static str,
+    /// Apply the migration, returning how many places were changed
+    apply: fn(&mut DocumentMut) -> usize,
+}
+
+/// All migrations, oldest first.
+const MIGRATIONS: [Migration; 2] = [
+    Migration {
+        from: Version(0, 1),
+        description: "resize tables use `grow` / `shrink` keys instead of `change`, and `unit` instead of `px`",
+        apply: migrate_resize,
+    },
+    Migration {
+        from: Version(0, 2),
+        description: "`criteria-focus = [...]` is replaced by `matching = { criteria = [...], focus = {} }`",
+        apply: migrate_criteria_focus,
+    },
+];
+
+/// The version a document declares.
+pub fn version(doc: &DocumentMut) -> Result<Version, MigrateError> {
+    match doc.get("version") {
+        None => Ok(OLDEST),
+        Some(v) => match v.as_str() {
+            Some(s) => s.parse(),
+            None => Err(MigrateError::Invalid(v.to_string().trim().to_string())),
+        }
+    }
+}
+
+/// Upgrade a document to the current version of the format. Comments and formatting are kept, since
+/// everything is edited in place. Returns a description of each migration that changed something.
+pub fn migrate(doc: &mut DocumentMut) -> Result<Vec<&'static str>, MigrateError> {
+    let from = version(doc)?;
+    if from > CURRENT {
+        return Err(MigrateError::Unsupported(from))
+    }
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        let changes = (migration.apply)(doc);
+        log::debug!("Migration from {}: {} change(s)", migration.from, changes);
+        if changes > 0 { applied.push(migration.description) }
+    }
+    if from < CURRENT {
+        match doc.get_mut("version") {
+            Some(Item::Value(v)) => {
+                let decor = v.decor().clone();
+                *v = Value::from(CURRENT.to_string());
+                *v.decor_mut() = decor;
+            }
+            _ => { doc.insert("version", toml_edit::value(CURRENT.to_string())); }
+        }
+    }
+    Ok(applied)
+}
+
+/// Call `f` on every key / value pair in a document (including those nested in arrays and inline
+/// tables). If `f` returns true, the value was handled and is not visited any further.
+fn visit(table: &mut dyn TableLike, f: &mut dyn FnMut(&str, &mut Item) -> bool) {
+    for (key, item) in table.iter_mut() {
+        if f(key.get(), item) { continue }
+        match item {
+            Item::Table(t) => visit(t, f),
+            Item::ArrayOfTables(a) => a.iter_mut().for_each(|t| visit(t, f)),
+            Item::Value(v) => visit_value(v, f),
+            Item::None => (),
+        }
+    }
+}
+
+fn visit_value(value: &mut Value, f: &mut dyn FnMut(&str, &mut Item) -> bool) {
+    match value {
+        Value::InlineTable(t) => visit(t, f),
+        Value::Array(a) => a.iter_mut().for_each(|v| visit_value(v, f)),
+        _ => (),
+    }
+}
+
+/// 0.1 -> 0.2: `resize = { change = "shrink", height = 10, px = true }` becomes
+/// `resize = { shrink = { height = 10, unit = "px" } }`.
+fn migrate_resize(doc: &mut DocumentMut) -> usize {
+    let mut changes = 0;
+    visit(doc.as_table_mut(), &mut |key, item| {
+        if key != "resize" { return false }
+        let Some(new) = new_resize(item) else { return false };
+        *item = new;
+        changes += 1;
+        true
+    });
+    changes
+}
+
+fn new_resize(item: &Item) -> Option<Item> {
+    let old = item.as_table_like()?;
+    let change = old.get("change")?.as_str()?;
+    let flag = |name: &str| old.get(name).and_then(|v| v.as_bool());
+    let unit = if flag("ppt") == Some(true) || flag("px") == Some(false) { "ppt" } else { "px" };
+    let mut params = InlineTable::new();
+    for (key, new_key) in [("width", "width"), ("x", "width"), ("height", "height"), ("y", "height")] {
+        if let Some(v) = old.get(key).and_then(|v| v.as_value()) {
+            let mut v = v.clone();
+            v.decor_mut().clear();
+            params.insert(new_key, v);
+        }
+    }
+    params.insert("unit", unit.into());
+    let mut resize = match change {
+        "set" => params,
+        "grow" | "shrink" => {
+            let mut outer = InlineTable::new();
+            outer.insert(change, params.into());
+            outer
+        }
+        _ => return None,
+    };
+    InlineTable::fmt(&mut resize);
+    match item {
+        Item::Value(v) => {
+            let mut new = Value::InlineTable(resize);
+            *new.decor_mut() = v.decor().clone();
+            Some(Item::Value(new))
+        }
+        _ => Some(Item::Table(resize.into_table())),
+    }
+}
+
+/// 0.2 -> 0.3: `criteria-focus = [...]` becomes `matching = { criteria = [...], focus = {} }`.
+fn migrate_criteria_focus(doc: &mut DocumentMut) -> usize {
+    let mut changes = 0;
+    visit(doc.as_table_mut(), &mut |key, item| {
+        if key != "criteria-focus" { return false }
+        if let Item::ArrayOfTables(criteria) = item {
+            // `[[...criteria-focus]]` becomes `[...matching]` with `[[...matching.criteria]]`
+            let mut matching = Table::new();
+            if let Some(position) = criteria.iter().next().and_then(|t| t.position()) {
+                matching.set_position(position);
+            }
+            matching.insert("focus", toml_edit::value(InlineTable::new()));
+            matching.insert("criteria", Item::ArrayOfTables(std::mem::take(criteria)));
+            *item = Item::Table(matching);
+            changes += 1;
+            return true
+        }
+        let Some(criteria) = item.as_value() else { return false };
+        let mut criteria = criteria.clone();
+        let decor = criteria.decor().clone();
+        criteria.decor_mut().clear();
+        let mut matching = InlineTable::new();
+        matching.insert("criteria", criteria);
+        matching.insert("focus", InlineTable::new().into());
+        InlineTable::fmt(&mut matching);
+        let mut new = Value::InlineTable(matching);
+        *new.decor_mut() = decor;
+        *item = Item::Value(new);
+        changes += 1;
+        true
+    });
+    // the key itself can't be renamed while visiting, so do that afterwards
+    rename_keys(doc.as_table_mut(), "criteria-focus", "matching", &|item| {
+        item.is_value() || item.as_table().is_some_and(|t| t.contains_key("criteria"))
+    });
+    changes
+}
+
+/// Rename every `from` key in a document whose item matches `converted` to `to`, keeping its decor
+/// and item.
+fn rename_keys(table: &mut Table, from: &str, to: &str, converted: &dyn Fn(&Item) -> bool) {
+    if table.get(from).is_some_and(converted) {
+        let (key, item) = table.remove_entry(from).unwrap();
+        table.insert_formatted(&renamed(&key, to), item);
+    }
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(t) => rename_keys(t, from, to, converted),
+            Item::ArrayOfTables(a) => a.iter_mut().for_each(|t| rename_keys(t, from, to, converted)),
+            Item::Value(v) => rename_value_keys(v, from, to),
+            Item::None => (),
+        }
+    }
+}
+
+fn rename_value_keys(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::InlineTable(t) => {
+            if let Some((key, v)) = t.remove_entry(from) {
+                t.insert_formatted(&renamed(&key, to), v);
+                // the renamed key moves to the end, so the spacing around the other keys is off
+                InlineTable::fmt(t);
+            }
+            t.iter_mut().for_each(|(_, v)| rename_value_keys(v, from, to));
+        }
+        Value::Array(a) => a.iter_mut().for_each(|v| rename_value_keys(v, from, to)),
+        _ => (),
+    }
+}
+
+fn renamed(key: &Key, name: &str) -> Key {
+    let mut new = Key::new(name);
+    *new.leaf_decor_mut() = key.leaf_decor().clone();
+    *new.dotted_decor_mut() = key.dotted_decor().clone();
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sway::config::Config;
+
+    #[test]
+    fn test_version() {
+        let doc = |s: &str| s.parse::<DocumentMut>().unwrap();
+        assert_eq!(version(&doc("exec = []")).unwrap(), OLDEST);
+        assert_eq!(version(&doc("version = \"0.1\"")).unwrap(), Version(0, 1));
+        assert!(matches!(version(&doc("version = 2")), Err(MigrateError::Invalid(_))));
+        assert!(matches!(migrate(&mut doc("version = \"1.0\"")), Err(MigrateError::Unsupported(Version(1, 0)))));
+    }
+
+    #[test]
+    fn test_migrate_resize() {
+        let mut doc: DocumentMut = "version = \"0.1\" # format version\
+            \n[modes.resize.bindsym]\
+            \n# shrink vertically\
+            \n\"Up\".resize = { change = \"shrink\", height = 10, px = true } # comment\
+            \n\"Left\".resize = { change = \"set\", x = 50, px = false }\
+            \n[[for-window]]\
+            \ncriteria = [{ app-id = \"foot\" }]\
+            \nresize = { change = \"grow\", width = 5 }\n".parse().unwrap();
+        assert_eq!(migrate(&mut doc).unwrap().len(), 1);
+        assert_eq!(doc.to_string(), "version = \"0.3\" # format version\
+            \n[modes.resize.bindsym]\
+            \n# shrink vertically\
+            \n\"Up\".resize = { shrink = { height = 10, unit = \"px\" } } # comment\
+            \n\"Left\".resize = { width = 50, unit = \"ppt\" }\
+            \n[[for-window]]\
+            \ncriteria = [{ app-id = \"foot\" }]\
+            \nresize = { grow = { width = 5, unit = \"px\" } }\n");
+        // migrating again doesn't change anything
+        assert!(migrate(&mut doc).unwrap().is_empty());
+
+        let cfg: Config = toml::from_str(&doc.to_string()).unwrap();
+        assert!(cfg.to_string().contains("bindsym Up resize shrink height 10 px"));
+        assert!(cfg.to_string().contains("bindsym Left resize set width 50 ppt"));
+    }
+
+    #[test]
+    fn test_migrate_versionless() {
+        // files from before the `version` key existed are the oldest version
+        let mut doc: DocumentMut = "exec = [\"mako\"]\
+            \n[bindsym]\
+            \n\"Mod4+a\".criteria-focus = [{ con-mark = \"a\" }]\n".parse().unwrap();
+        assert_eq!(migrate(&mut doc).unwrap().len(), 1);
+        assert_eq!(doc.to_string(), "exec = [\"mako\"]\
+            \nversion = \"0.3\"\
+            \n[bindsym]\
+            \n\"Mod4+a\".matching = { criteria = [{ con-mark = \"a\" }], focus = {} }\n");
+        let cfg: Config = toml::from_str(&doc.to_string()).unwrap();
+        assert!(cfg.to_string().contains("bindsym Mod4+a [con_mark=\"a\"] focus\n"));
+
+        let mut doc: DocumentMut = "exec = [\"mako\"]\n".parse().unwrap();
+        assert!(migrate(&mut doc).unwrap().is_empty());
+        assert_eq!(doc.to_string(), "exec = [\"mako\"]\nversion = \"0.3\"\n");
+    }
+
+    #[test]
+    fn test_migrate_criteria_focus() {
+        let mut doc: DocumentMut = "version = \"0.2\"\
+            \n[bindsym]\
+            \n\"$mod+a\".criteria-focus = [{ con-mark = \"a\" }] # jump to a\
+            \n\"$mod+b\" = { criteria-focus = [{ app-id = \"foot\" }], description = \"Focus foot\" }\n".parse().unwrap();
+        assert_eq!(migrate(&mut doc).unwrap().len(), 1);
+        assert_eq!(doc.to_string(), "version = \"0.3\"\
+            \n[bindsym]\
+            \n\"$mod+a\".matching = { criteria = [{ con-mark = \"a\" }], focus = {} } # jump to a\
+            \n\"$mod+b\" = { description = \"Focus foot\", matching = { criteria = [{ app-id = \"foot\" }], focus = {} } }\n");
+        assert!(migrate(&mut doc).unwrap().is_empty());
+
+        let cfg: Config = toml::from_str(&doc.to_string()).unwrap();
+        assert!(cfg.to_string().contains("bindsym $mod+a [con_mark=\"a\"] focus\n"));
+        assert!(cfg.to_string().contains("bindsym $mod+b [app_id=\"foot\"] focus\n"));
+
+        // arrays of tables become a table of their own
+        let mut doc: DocumentMut = "version = \"0.2\"\
+            \n[bindsym.\"$mod+b\"]\
+            \nexec = \"foot\"\
+            \n\
+            \n[[bindsym.\"$mod+c\".criteria-focus]]\
+            \napp-id = \"foot\"\
+            \n\
+            \n[[bindsym.\"$mod+c\".criteria-focus]]\
+            \ncon-mark = \"c\"\n".parse().unwrap();
+        assert_eq!(migrate(&mut doc).unwrap().len(), 1);
+        assert_eq!(doc.to_string(), "version = \"0.3\"\
+            \n[bindsym.\"$mod+b\"]\
+            \nexec = \"foot\"\
+            \n\
+            \n[bindsym.\"$mod+c\".matching]\
+            \nfocus = {}\
+            \n\
+            \n[[bindsym.\"$mod+c\".matching.criteria]]\
+            \napp-id = \"foot\"\
+            \n\
+            \n[[bindsym.\"$mod+c\".matching.criteria]]\
+            \ncon-mark = \"c\"\n");
+        let cfg: Config = toml::from_str(&doc.to_string()).unwrap();
+        assert!(cfg.to_string().contains("bindsym $mod+c [app_id=\"foot\" con_mark=\"c\"] focus\n"));
+    }
+}
@@ -0,0 +1,237 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use thiserror::Error;
+use toml::{Table, Value};
+
+/// Errors that can occur while selecting or applying a profile.
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Profile {0} is not defined")]
+    Unknown(String),
+    #[error("Profile {0} is invalid: {1}")]
+    Invalid(String, String),
+}
+
+/// Rules used to automatically select a profile.
+///
+/// All specified rules must match for the profile to be selected. A profile without any rules is
+/// never selected automatically, but can still be chosen with `--profile`.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProfileMatch {
+    /// Hostnames that this profile applies to (any of them may match)
+    #[serde(default)]
+    hostname: Option<Vec<String>>,
+    /// Environment variables that must be set to the given values
+    #[serde(default)]
+    env: Option<HashMap<String, String>>,
+}
+
+impl ProfileMatch {
+    fn is_empty(&self) -> bool {
+        self.hostname.is_none() && self.env.is_none()
+    }
+
+    fn matches(&self, host: &Host) -> bool {
+        let hostname = match &self.hostname {
+            Some(names) => host.hostname.as_ref().is_some_and(|h| names.contains(h)),
+            None => true
+        };
+        let env = match &self.env {
+            Some(vars) => vars.iter().all(|(k, v)| host.env.get(k) == Some(v)),
+            None => true
+        };
+        !self.is_empty() && hostname && env
+    }
+}
+
+/// Information about the current machine used to match profiles.
+#[derive(Clone, Debug, Default)]
+pub struct Host {
+    pub hostname: Option<String>,
+    pub env: HashMap<String, String>,
+}
+
+impl Host {
+    /// Gather the hostname and environment of the machine we are running on.
+    pub fn current() -> Self {
+        let hostname = gethostname::gethostname().into_string().ok();
+        Host { hostname, env: std::env::vars().collect() }
+    }
+}
+
+/// Why a profile was selected.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Reason {
+    /// Explicitly requested via the command line
+    Requested,
+    /// Matched by the profile's `match` rules
+    Matched,
+}
+
+/// The profile that was merged into the base configuration.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Selected {
+    pub name: String,
+    pub reason: Reason,
+}
+
+impl Display for Selected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.reason {
+            Reason::Requested => write!(f, "{} (requested with --profile)", self.name),
+            Reason::Matched => write!(f, "{} (matched hostname/environment rules)", self.name),
+        }
+    }
+}
+
+/// Select a profile from the `[profile]` table and merge it over the rest of the document.
+///
+/// If `requested` is given, that profile is used regardless of its match rules. Otherwise the
+/// first profile (in name order) whose rules match `host` is used. The `[profile]` table is
+/// removed from the document either way, so the result can be deserialized into a
+/// [Config](crate::sway::config::Config) directly.
+pub fn apply(doc: &mut Table, requested: Option<&str>, host: &Host) -> Result<Option<Selected>, ProfileError> {
+    let profiles = match doc.remove("profile") {
+        Some(Value::Table(t)) => t,
+        Some(_) => return Err(ProfileError::Invalid("profile".to_string(), "expected a table".to_string())),
+        None => Table::new(),
+    };
+
+    let selected = match requested {
+        Some(name) => {
+            if !profiles.contains_key(name) { return Err(ProfileError::Unknown(name.to_string())) }
+            Some(Selected { name: name.to_string(), reason: Reason::Requested })
+        }
+        None => {
+            let mut matched = Vec::new();
+            for (name, profile) in profiles.iter() {
+                if rules(name, profile)?.matches(host) { matched.push(name.clone()) }
+            }
+            if matched.len() > 1 {
+                log::warn!("Multiple profiles matched ({}), using {}", matched.join(", "), matched[0]);
+            }
+            if matched.is_empty() && !profiles.is_empty() {
+                log::warn!("No profile matched this host, using the base configuration");
+            }
+            matched.into_iter().next().map(|name| Selected { name, reason: Reason::Matched })
+        }
+    };
+
+    if let Some(Selected { name, .. }) = &selected {
+        log::info!("Applying profile {}", name);
+        let overlay = match profiles.get(name) {
+            Some(Value::Table(t)) => t.clone(),
+            _ => return Err(ProfileError::Invalid(name.clone(), "expected a table".to_string())),
+        };
+        merge(doc, without_rules(overlay));
+    }
+    Ok(selected)
+}
+
+fn rules(name: &str, profile: &Value) -> Result<ProfileMatch, ProfileError> {
+    match profile {
+        Value::Table(t) => match t.get("match") {
+            Some(m) => m.clone().try_into()
+                .map_err(|e: toml::de::Error| ProfileError::Invalid(name.to_string(), e.message().to_string())),
+            None => Ok(ProfileMatch::default()),
+        },
+        _ => Err(ProfileError::Invalid(name.to_string(), "expected a table".to_string())),
+    }
+}
+
+fn without_rules(mut overlay: Table) -> Table {
+    overlay.remove("match");
+    overlay
+}
+
+/// Recursively merge `overlay` over `base`. Tables are merged key by key; everything else
+/// (including arrays) is replaced outright.
+fn merge(base: &mut Table, overlay: Table) {
+    for (k, v) in overlay {
+        match (base.get_mut(&k), v) {
+            (Some(Value::Table(b)), Value::Table(o)) => merge(b, o),
+            (_, v) => { base.insert(k, v); }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "exec = [\"mako\"]\
+        \n[set]\
+        \nmod = \"Mod4\"\
+        \nterm = \"alacritty\"\
+        \n[profile.laptop]\
+        \nmatch.hostname = [\"thinkpad\"]\
+        \nexec = [\"nm-applet\"]\
+        \nset.term = \"foot\"\
+        \n[profile.vm]\
+        \nmatch.env = { container = \"vm\" }\
+        \nset.mod = \"Mod1\"";
+
+    fn host(hostname: &str, env: &[(&str, &str)]) -> Host {
+        Host {
+            hostname: Some(hostname.to_string()),
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        }
+    }
+
+    #[test]
+    fn test_match_hostname() {
+        let mut doc: Table = toml::from_str(DOC).unwrap();
+        let selected = apply(&mut doc, None, &host("thinkpad", &[])).unwrap();
+        assert_eq!(selected, Some(Selected { name: "laptop".to_string(), reason: Reason::Matched }));
+        assert!(doc.get("profile").is_none());
+        assert_eq!(doc["set"]["mod"].as_str(), Some("Mod4"));
+        assert_eq!(doc["set"]["term"].as_str(), Some("foot"));
+        assert_eq!(doc["exec"].as_array().unwrap().len(), 1);
+        assert_eq!(doc["exec"][0].as_str(), Some("nm-applet"));
+    }
+
+    #[test]
+    fn test_match_env() {
+        let mut doc: Table = toml::from_str(DOC).unwrap();
+        let selected = apply(&mut doc, None, &host("desktop", &[("container", "vm")])).unwrap();
+        assert_eq!(selected.unwrap().name, "vm");
+        assert_eq!(doc["set"]["mod"].as_str(), Some("Mod1"));
+    }
+
+    #[test]
+    fn test_no_match() {
+        let mut doc: Table = toml::from_str(DOC).unwrap();
+        assert_eq!(apply(&mut doc, None, &host("desktop", &[])).unwrap(), None);
+        assert_eq!(doc["set"]["term"].as_str(), Some("alacritty"));
+    }
+
+    #[test]
+    fn test_requested() {
+        let mut doc: Table = toml::from_str(DOC).unwrap();
+        let selected = apply(&mut doc, Some("vm"), &host("thinkpad", &[])).unwrap();
+        assert_eq!(selected, Some(Selected { name: "vm".to_string(), reason: Reason::Requested }));
+        assert_eq!(doc["set"]["term"].as_str(), Some("alacritty"));
+
+        let mut doc: Table = toml::from_str(DOC).unwrap();
+        assert!(matches!(apply(&mut doc, Some("nope"), &Host::default()), Err(ProfileError::Unknown(_))));
+    }
+}
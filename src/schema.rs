@@ -0,0 +1,130 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use schemars::{json_schema, generate::SchemaSettings, Schema};
+use serde_json::{json, Value};
+use crate::profile::ProfileMatch;
+use crate::sway::config::Config;
+
+/// Generate a JSON Schema for swayconf's TOML format.
+///
+/// Most of this is derived from [Config], but profiles are handled separately (they are merged
+/// into the document before it is parsed), so their schema is added here. A profile can contain
+/// anything the base config can, plus a `match` table.
+pub fn generate() -> Schema {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    let profile_match = generator.subschema_for::<ProfileMatch>();
+    let mut schema = generator.into_root_schema_for::<Config>();
+    schema.insert("title".to_string(), "swayconf configuration".into());
+    let profile = json_schema!({
+        "description": "Per-host profiles, merged over the base configuration",
+        "type": "object",
+        "additionalProperties": {
+            "allOf": [{ "$ref": "#" }],
+            "properties": { "match": profile_match }
+        }
+    });
+    if let Some(props) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+        props.insert("profile".to_string(), profile.to_value());
+    }
+    if let Some(defs) = schema.get_mut("$defs").and_then(|d| d.as_object_mut()) {
+        // KeylessBindsym has its own copy of the Runtime variants, since Runtime is flattened into it
+        for name in ["Runtime", "KeylessBindsym"] {
+            if let Some(def) = defs.get_mut(name) { allow_empty_tables(def) }
+        }
+        if let Some(def) = defs.get_mut("Runtime") { allow_sibling_keys(def) }
+    }
+    schema
+}
+
+/// Parameterless commands are written as empty tables (`kill = {}`, see [Runtime]), but schemars
+/// describes them in serde's string form (`"kill"`) or, when flattened, as `kill = null` (which
+/// TOML can't express). Allow empty tables instead.
+///
+/// [Runtime]: crate::sway::runtime::Runtime
+fn allow_empty_tables(def: &mut Value) {
+    let empty = json!({ "type": "object", "maxProperties": 0 });
+    let Some(variants) = def.get_mut("oneOf").and_then(|v| v.as_array_mut()) else { return };
+    for variant in variants.iter_mut() {
+        if let Some(props) = variant.get_mut("properties").and_then(|p| p.as_object_mut()) {
+            props.values_mut().filter(|p| **p == json!({ "type": "null" })).for_each(|p| *p = empty.clone());
+        }
+    }
+    // documented variants get their own `const` schema, undocumented ones are grouped in an `enum`
+    let names: Vec<(String, Value)> = variants.iter()
+        .flat_map(|v| {
+            let description = v.get("description").cloned().unwrap_or(Value::Null);
+            let names = match (v.get("const"), v.get("enum")) {
+                (Some(c), _) => vec![c.clone()],
+                (None, Some(Value::Array(e))) => e.clone(),
+                _ => Vec::new(),
+            };
+            names.into_iter().filter_map(move |n| n.as_str().map(|n| (n.to_string(), description.clone())))
+        })
+        .collect();
+    for (name, description) in names {
+        let mut table = json!({
+            "type": "object",
+            "properties": { name.clone(): empty },
+            "required": [name]
+        });
+        if !description.is_null() { table["description"] = description }
+        variants.push(table);
+    }
+}
+
+/// Nested commands (`for-window`, `matching`, `chain`) are flattened into a table next to other
+/// keys (such as `criteria`), and refer to [Runtime] with a `$ref`. Variants that forbid
+/// additional properties would reject those keys, so allow them.
+///
+/// [Runtime]: crate::sway::runtime::Runtime
+fn allow_sibling_keys(def: &mut Value) {
+    let Some(variants) = def.get_mut("oneOf").and_then(|v| v.as_array_mut()) else { return };
+    for variant in variants.iter_mut().filter_map(|v| v.as_object_mut()) {
+        variant.remove("additionalProperties");
+    }
+}
+
+/// Render the schema as pretty-printed JSON.
+pub fn render() -> String {
+    serde_json::to_string_pretty(&generate()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema() {
+        let schema = generate().to_value();
+        let props = &schema["properties"];
+        for key in ["set", "bindsym", "modes", "exec", "output", "include", "profile"] {
+            assert!(props.get(key).is_some(), "missing property {key}");
+        }
+        let defs = schema["$defs"].as_object().unwrap();
+        for def in ["Runtime", "KeylessBindsym", "TogglableBool", "ProfileMatch", "Background"] {
+            assert!(defs.contains_key(def), "missing definition {def}");
+        }
+        // runtime commands are kebab-case, like in TOML
+        let runtime = serde_json::to_string(&defs["Runtime"]).unwrap();
+        assert!(runtime.contains("\"exec-always\""));
+        assert!(runtime.contains("\"kill\""));
+        assert!(runtime.contains("\"maxProperties\":0"));
+        assert!(!runtime.contains("additionalProperties"));
+        assert_eq!(schema["properties"]["profile"]["additionalProperties"]["properties"]["match"]["$ref"],
+                   Value::from("#/$defs/ProfileMatch"));
+    }
+}
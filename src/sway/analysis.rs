@@ -0,0 +1,215 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use crate::sway::config::{BindKind, Binding, Config};
+use crate::sway::options::bind::{Bind, BindKeys};
+use crate::sway::vars::Variables;
+
+/// Modifier names recognized by Sway, along with their canonical spelling. The order of this list
+/// is the order modifiers are sorted into when normalizing.
+pub const MODIFIERS: [(&str, &[&str]); 8] = [
+    ("Shift", &["shift"]),
+    ("Lock", &["lock", "caps"]),
+    ("Control", &["control", "ctrl"]),
+    ("Mod1", &["mod1", "alt"]),
+    ("Mod2", &["mod2"]),
+    ("Mod3", &["mod3"]),
+    ("Mod4", &["mod4", "super"]),
+    ("Mod5", &["mod5"]),
+];
+
+/// A key combination in a canonical form that can be compared with other key combinations.
+///
+/// Variables are substituted, modifier aliases (such as `Super` and `Ctrl`) are replaced with their
+/// canonical names, and both modifiers and keys are sorted.
+#[derive(PartialEq, Eq, Clone, Debug, Hash, PartialOrd, Ord)]
+pub struct NormalizedKeys {
+    modifiers: Vec<usize>,
+    keys: Vec<String>,
+}
+
+impl NormalizedKeys {
+    pub fn new(keys: &str, vars: &Variables) -> Self {
+        let mut modifiers = Vec::new();
+        let mut rest = Vec::new();
+        for key in BindKeys::parse(&vars.substitute(keys)).keys() {
+            let lower = key.to_lowercase();
+            match MODIFIERS.iter().position(|(_, aliases)| aliases.contains(&lower.as_str())) {
+                Some(m) => modifiers.push(m),
+                None => rest.push(key.clone()),
+            }
+        }
+        modifiers.sort();
+        modifiers.dedup();
+        rest.sort();
+        NormalizedKeys { modifiers, keys: rest }
+    }
+
+    /// The same key combination with all keysyms in lowercase.
+    fn case_folded(&self) -> Self {
+        let mut keys: Vec<String> = self.keys.iter().map(|k| k.to_lowercase()).collect();
+        keys.sort();
+        NormalizedKeys { modifiers: self.modifiers.clone(), keys }
+    }
+}
+
+impl Display for NormalizedKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let keys: Vec<&str> = self.modifiers.iter().map(|m| MODIFIERS[*m].0)
+            .chain(self.keys.iter().map(|k| k.as_str()))
+            .collect();
+        write!(f, "{}", keys.join("+"))
+    }
+}
+
+/// Types of binding conflicts.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ConflictKind {
+    /// The bindings are identical once normalized, so only one of them will take effect.
+    Duplicate,
+    /// The bindings only differ by the case of their keysyms. Sway looks up keysyms
+    /// case-insensitively, so these are most likely duplicates as well.
+    NearDuplicate,
+    /// The binding replaces one generated by `[autogen]`, which is dropped.
+    Generated,
+}
+
+/// A group of bindings in the same mode that shadow each other.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub mode: String,
+    pub bind: BindKind,
+    /// The key combinations as they were written in the config
+    pub keys: Vec<String>,
+    /// The normalized key combination
+    pub normalized: String,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let keys = self.keys.join(", ");
+        match self.kind {
+            ConflictKind::Duplicate =>
+                write!(f, "{} {} in mode {} are the same binding ({})", self.bind, keys, self.mode, self.normalized),
+            ConflictKind::NearDuplicate =>
+                write!(f, "{} {} in mode {} only differ by keysym case ({})", self.bind, keys, self.mode, self.normalized),
+            ConflictKind::Generated =>
+                write!(f, "{} {} in mode {} replaces a binding generated by autogen ({})", self.bind, keys, self.mode, self.normalized),
+        }
+    }
+}
+
+/// Flags that make otherwise identical bindings distinct in Sway.
+fn identity_flags(binding: &Binding) -> Vec<String> {
+    let mut flags: Vec<String> = binding.bind.flags().enabled()
+        .filter(|f| !matches!(f, Bind::NoWarn | Bind::NoRepeat | Bind::ToCode))
+        .map(|f| f.to_string())
+        .collect();
+    flags.sort();
+    flags
+}
+
+/// Find all duplicate and near-duplicate bindings in a config, per mode.
+pub fn binding_conflicts(cfg: &Config) -> Vec<Conflict> {
+    let vars = cfg.variables();
+    type Key = (String, BindKind, Vec<String>, NormalizedKeys);
+    let mut exact: BTreeMap<Key, Vec<String>> = BTreeMap::new();
+    for binding in cfg.bindings() {
+        let key = (binding.mode.to_string(), binding.kind, identity_flags(&binding), NormalizedKeys::new(binding.keys, &vars));
+        exact.entry(key).or_default().push(binding.keys.to_string());
+    }
+
+    let mut conflicts = Vec::new();
+    let mut folded: BTreeMap<Key, Vec<(NormalizedKeys, Vec<String>)>> = BTreeMap::new();
+    for ((mode, kind, flags, normalized), mut keys) in exact {
+        keys.sort();
+        if keys.len() > 1 {
+            conflicts.push(Conflict {
+                kind: ConflictKind::Duplicate,
+                mode: mode.clone(),
+                bind: kind,
+                keys: keys.clone(),
+                normalized: normalized.to_string(),
+            });
+        }
+        if kind == BindKind::Sym {
+            folded.entry((mode, kind, flags, normalized.case_folded())).or_default().push((normalized, keys));
+        }
+    }
+    for ((mode, kind, _, _), group) in folded {
+        if group.len() > 1 {
+            conflicts.push(Conflict {
+                kind: ConflictKind::NearDuplicate,
+                mode,
+                bind: kind,
+                keys: group.iter().flat_map(|(_, k)| k.iter().cloned()).collect(),
+                normalized: group.iter().map(|(n, _)| n.to_string()).collect::<Vec<String>>().join(" / "),
+            });
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let vars = Variables::new(&[("mod".to_string(), "Mod4".to_string())].into()).unwrap();
+        let a = NormalizedKeys::new("Shift+$mod+Return", &vars);
+        let b = NormalizedKeys::new("super+shift+Return", &vars);
+        let c = NormalizedKeys::new("Mod4+Control+Mod1+Shift+l", &vars);
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), "Shift+Mod4+Return");
+        assert_eq!(c.to_string(), "Shift+Control+Mod1+Mod4+l");
+        assert_eq!(NormalizedKeys::new("Ctrl+Alt+x", &vars), NormalizedKeys::new("Mod1+Control+x", &vars));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let cfg: Config = toml::from_str("[set]\
+            \nmod = \"Mod4\"\
+            \n[bindsym]\
+            \n\"$mod+Shift+space\".exec = \"rofi -show drun\"\
+            \n\"$mod+Shift+Space\".floating = \"toggle\"\
+            \n\"$mod+q\".kill = {}\
+            \n\"Super+q\".exec = \"true\"\
+            \n\"$mod+x\".exec = \"true\"\
+            \n\"Mod4+x\" = { exec = \"true\", flags = { release = true } }\
+            \n[modes.resize.bindsym]\
+            \n\"$mod+q\".mode = \"default\"").unwrap();
+        let conflicts = binding_conflicts(&cfg);
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.contains(&Conflict {
+            kind: ConflictKind::Duplicate,
+            mode: "default".to_string(),
+            bind: BindKind::Sym,
+            keys: vec!["$mod+q".to_string(), "Super+q".to_string()],
+            normalized: "Mod4+q".to_string(),
+        }));
+        assert!(conflicts.contains(&Conflict {
+            kind: ConflictKind::NearDuplicate,
+            mode: "default".to_string(),
+            bind: BindKind::Sym,
+            keys: vec!["$mod+Shift+Space".to_string(), "$mod+Shift+space".to_string()],
+            normalized: "Shift+Mod4+Space / Shift+Mod4+space".to_string(),
+        }));
+    }
+}
@@ -0,0 +1,135 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::sway::autogen::Generated;
+use crate::sway::config::KeylessBindsym;
+use crate::sway::criteria::{Criteria, CriteriaVec};
+use crate::sway::options::ArgMap;
+use crate::sway::options::focus::Focus;
+use crate::sway::options::mark::{MarkMode, MarkParams};
+use crate::sway::runtime::{Chain, Runtime, Separator};
+
+/// Vim-style marks. This generates two modes: one where pressing a key marks the focused window
+/// with that key, and one where pressing a key focuses the window marked with it.
+///
+/// ```toml
+/// [autogen.marks]
+/// keys = "abcdefghijklmnopqrstuvwxyz"
+/// set = "$mod+m"
+/// jump = "$mod+apostrophe"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct MarkModes {
+    /// Keys that can be used as marks. Each character is a key, and is also the name of its mark.
+    keys: MarkKeys,
+    /// Binding that enters the mode for setting marks
+    set: String,
+    /// Binding that enters the mode for jumping to marks
+    jump: String,
+    /// Name of the mode for setting marks
+    #[serde(default = "default_set_mode")]
+    set_mode: String,
+    /// Name of the mode for jumping to marks
+    #[serde(default = "default_jump_mode")]
+    jump_mode: String,
+}
+
+/// Keysym names for the punctuation characters that can be used as mark keys.
+const PUNCTUATION: [(char, &str); 11] = [
+    ('\'', "apostrophe"), (';', "semicolon"), (',', "comma"), ('.', "period"), ('/', "slash"),
+    ('-', "minus"), ('=', "equal"), ('[', "bracketleft"), (']', "bracketright"),
+    ('\\', "backslash"), ('`', "grave"),
+];
+
+/// The keys of [MarkModes]. Letters and digits are their own keysyms and mark names, while
+/// punctuation is bound by (and marked with) its keysym name, so `'` binds `apostrophe`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String")]
+pub struct MarkKeys(String);
+
+impl TryFrom<String> for MarkKeys {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.chars().find(|c| keysym(*c).is_none()) {
+            Some(c) => Err(format!("{c:?} can't be used as a mark key (expected letters, digits or one of {})",
+                                   PUNCTUATION.iter().map(|(p, _)| p.to_string()).collect::<String>())),
+            None => Ok(MarkKeys(value)),
+        }
+    }
+}
+
+impl MarkKeys {
+    /// The keysym of each key.
+    fn keysyms(&self) -> impl Iterator<Item = String> + '_ {
+        self.0.chars().filter_map(keysym)
+    }
+}
+
+fn keysym(c: char) -> Option<String> {
+    if c.is_ascii_alphanumeric() { return Some(c.to_string()) }
+    PUNCTUATION.iter().find(|(p, _)| *p == c).map(|(_, name)| name.to_string())
+}
+
+fn default_set_mode() -> String { "mark".to_string() }
+
+fn default_jump_mode() -> String { "goto".to_string() }
+
+fn bind(command: Runtime, description: String) -> KeylessBindsym {
+    KeylessBindsym::new(ArgMap::default(), command).with_description(&description, "marks")
+}
+
+/// Bindings for each key in a mode (which run a command and then leave the mode), plus
+/// Escape / Return to leave the mode without doing anything.
+fn mode(keys: &MarkKeys, command: impl Fn(&str) -> (Runtime, String)) -> Vec<(String, KeylessBindsym)> {
+    let mut bindings: Vec<(String, KeylessBindsym)> = keys.keysyms()
+        .map(|key| {
+            let (runtime, description) = command(&key);
+            let chain = Chain::new(vec![runtime, Runtime::Mode("default".to_string())], Separator::Semicolon);
+            (key, bind(Runtime::Chain(chain), description))
+        })
+        .collect();
+    for key in ["Escape", "Return"] {
+        bindings.push((key.to_string(), bind(Runtime::Mode("default".to_string()), "Leave mode".to_string())));
+    }
+    bindings
+}
+
+impl MarkModes {
+    pub fn generate(&self) -> Generated {
+        let set = mode(&self.keys, |k| (
+            Runtime::Mark(MarkParams::new(k, Some(MarkMode::Add), false)),
+            format!("Set mark {k}"),
+        ));
+        let jump = mode(&self.keys, |k| (
+            Runtime::Matching {
+                criteria: CriteriaVec::from(vec![Criteria::ConMark(k.to_string())]),
+                command: Box::new(Runtime::Focus(Focus::Matched {})),
+            },
+            format!("Jump to mark {k}"),
+        ));
+        Generated {
+            bindsym: vec![
+                (self.set.clone(), bind(Runtime::Mode(self.set_mode.clone()), "Set a mark".to_string())),
+                (self.jump.clone(), bind(Runtime::Mode(self.jump_mode.clone()), "Jump to a mark".to_string())),
+            ],
+            modes: vec![(self.set_mode.clone(), set), (self.jump_mode.clone(), jump)],
+        }
+    }
+}
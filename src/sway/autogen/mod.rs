@@ -15,16 +15,54 @@
 //
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::sway::config::KeylessBindsym;
 
-/// Configuration structure for autogen functionality.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Vim-style marks.
+pub mod marks;
+
+/// Configuration structure for autogen functionality (the `[autogen]` table).
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
-struct Autogen {
+pub struct Autogen {
     /// Startup applications (automatically run with `exec --no-startup-id`)
+    #[serde(default)]
     startup: Vec<String>,
     /// Auto-generated bindsyms
+    #[serde(default)]
     bindsym: bool,
     /// Generating workspaces. This specifies what key(s) correspond to each workspace; you will
     /// specify commands under `autogen.bindsym.workspaces`
-    workspaces: Vec<String>
-}
\ No newline at end of file
+    #[serde(default)]
+    workspaces: Vec<String>,
+    /// Modes for setting and jumping to marks, vim style
+    #[serde(default)]
+    marks: Option<marks::MarkModes>,
+}
+
+/// Bindings and modes generated by autogen, to be merged into the config.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Generated {
+    /// Bindings outside of any mode, by keys
+    pub bindsym: Vec<(String, KeylessBindsym)>,
+    /// Bindings for each mode, by mode name and keys
+    pub modes: Vec<(String, Vec<(String, KeylessBindsym)>)>,
+}
+
+impl Generated {
+    fn extend(&mut self, other: Generated) {
+        self.bindsym.extend(other.bindsym);
+        self.modes.extend(other.modes);
+    }
+}
+
+impl Autogen {
+    pub fn generate(&self) -> Generated {
+        let mut generated = Generated::default();
+        if !self.startup.is_empty() || self.bindsym || !self.workspaces.is_empty() {
+            log::warn!("autogen.startup, autogen.bindsym and autogen.workspaces are not implemented yet, ignoring them");
+        }
+        if let Some(m) = &self.marks { generated.extend(m.generate()) }
+        generated
+    }
+}
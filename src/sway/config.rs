@@ -15,11 +15,17 @@
 //
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::PathBuf;
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use crate::sway::analysis::{Conflict, ConflictKind, NormalizedKeys};
+use crate::sway::autogen;
 use crate::sway::options;
-use crate::sway::options::{bind, exec, layout, ArgMap};
-use crate::sway::runtime::Runtime;
+use crate::sway::options::{appearance, bind, client, exec, floating, focus, gaps, layout, output, ArgMap};
+use crate::sway::runtime::{Runtime, RuntimeKind};
+use crate::sway::vars::Variables;
 
 /// Basic structure for a config file.
 ///
@@ -47,9 +53,13 @@ use crate::sway::runtime::Runtime;
 /// bindsym $mod+Shift exec ls -la
 /// bindsym $mod+X exec ~/beans.sh
 /// ```
-#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
+    /// Version of the swayconf TOML format this file is written for (such as "0.2"). Older files
+    /// can be upgraded with `swayconf migrate`.
+    #[serde(default)]
+    version: Option<String>,
     /// Set Sway config variables.
     #[serde(default)]
     set: Option<HashMap<String, String>>,
@@ -67,6 +77,27 @@ pub struct Config {
     /// Default orientation and workspace layout
     #[serde(default)]
     default: Option<Defaults>,
+    /// Borders, title bars and fonts
+    #[serde(flatten)]
+    appearance: Appearance,
+    /// Floating windows and mouse dragging
+    #[serde(flatten)]
+    floating: FloatingCfg,
+    /// Focus behavior
+    #[serde(default)]
+    focus: Option<FocusCfg>,
+    /// Default gaps between windows, in pixels
+    #[serde(default)]
+    gaps: Option<gaps::Gaps>,
+    /// Only show gaps when there is more than one window on a workspace
+    #[serde(default)]
+    smart_gaps: Option<gaps::SmartGaps>,
+    /// Per-workspace settings, by workspace name
+    #[serde(default)]
+    workspace: Option<HashMap<String, WorkspaceCfg>>,
+    /// Switch back to the previous workspace when switching to the one that is already focused
+    #[serde(default)]
+    workspace_auto_back_and_forth: Option<bool>,
     #[serde(default)]
     modes: Option<Modes>,
     /// User-defined bindsym commands
@@ -77,15 +108,173 @@ pub struct Config {
     bindcode: Option<HashMap<String, KeylessBindsym>>,
     #[serde(default)]
     bar: Option<Bar>,
+    /// Window border and title bar colors
+    #[serde(default)]
+    client: Option<ClientCfg>,
+    /// Output (monitor) configuration, by output name
+    #[serde(default)]
+    output: Option<HashMap<String, output::OutputParams>>,
+    /// Additional Sway config files to include
+    #[serde(default)]
+    include: Option<Vec<options::FilePath>>,
+    /// Automatically generated groups of bindings and modes
+    #[serde(default)]
+    autogen: Option<autogen::Autogen>,
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+impl Config {
+    /// All key bindings in this config, including those defined in modes.
+    ///
+    /// Bindings outside of a mode are reported as being in the `default` mode.
+    pub fn bindings(&self) -> Vec<Binding<'_>> {
+        let mut bindings = Vec::new();
+        collect_bindings(&mut bindings, "default", &self.bindsym, &self.bindcode);
+        if let Some(Modes(modes)) = &self.modes {
+            for (name, mode) in modes.iter() {
+                collect_bindings(&mut bindings, name, &mode.bindsym, &mode.bindcode);
+            }
+        }
+        bindings
+    }
+
+    /// Paths of all included Sway config files. These may contain glob patterns.
+    pub fn includes(&self) -> Vec<PathBuf> {
+        match &self.include {
+            Some(i) => i.iter().map(|p| PathBuf::from(p.to_string())).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Client color commands (from the `[client]` table).
+    pub fn client_commands(&self) -> Vec<Runtime> {
+        match &self.client {
+            Some(c) => c.opts().into_iter().map(Runtime::Client).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Output commands (from the `[output]` table), sorted by output name.
+    pub fn output_commands(&self) -> Vec<Runtime> {
+        let mut outputs: Vec<(&String, &output::OutputParams)> = match &self.output {
+            Some(o) => o.iter().collect(),
+            None => Vec::new(),
+        };
+        outputs.sort_by_key(|(name, _)| *name);
+        outputs.into_iter()
+            .map(|(name, params)| Runtime::Output { name: name.clone(), params: params.clone() })
+            .collect()
+    }
+
+    /// Top-level `bindsym` commands. Bindings in modes are not included, since modes can only be
+    /// defined in the config file.
+    pub fn bindsym_commands(&self) -> Vec<Runtime> {
+        let mut bindings: Vec<(&String, &KeylessBindsym)> = match &self.bindsym {
+            Some(b) => b.iter().collect(),
+            None => Vec::new(),
+        };
+        bindings.sort_by_key(|(keys, _)| *keys);
+        bindings.into_iter()
+            .map(|(keys, b)| Runtime::BindSym {
+                flags: b.flags.clone(),
+                keys: bind::BindKeys::parse(keys),
+                command: Box::new(b.command.clone()),
+            })
+            .collect()
+    }
+
+    /// Merge the bindings and modes generated by the `[autogen]` table into this config. Bindings
+    /// that are already defined are kept, and returned as [ConflictKind::Generated] conflicts.
+    pub fn expand_autogen(&mut self) -> Vec<Conflict> {
+        let Some(autogen) = &self.autogen else { return Vec::new() };
+        let generated = autogen.generate();
+        let vars = self.variables();
+        let mut conflicts = Vec::new();
+        let mut merge = |mode: &str, bindsym: &mut HashMap<String, KeylessBindsym>, bindings: Vec<(String, KeylessBindsym)>| {
+            for (keys, bind) in bindings {
+                let normalized = NormalizedKeys::new(&keys, &vars).to_string();
+                match bindsym.entry(keys) {
+                    Entry::Occupied(e) => conflicts.push(Conflict {
+                        kind: ConflictKind::Generated,
+                        mode: mode.to_string(),
+                        bind: BindKind::Sym,
+                        keys: vec![e.key().clone()],
+                        normalized,
+                    }),
+                    Entry::Vacant(e) => { e.insert(bind); }
+                }
+            }
+        };
+        merge("default", self.bindsym.get_or_insert_with(HashMap::new), generated.bindsym);
+        let Modes(modes) = self.modes.get_or_insert_with(Modes::default);
+        for (name, bindings) in generated.modes {
+            let mode = modes.entry(name.clone()).or_default();
+            merge(&name, mode.bindsym.get_or_insert_with(HashMap::new), bindings);
+        }
+        conflicts
+    }
+
+    /// The variables defined in the `[set]` table.
+    pub fn variables(&self) -> Variables {
+        match &self.set {
+            Some(s) => Variables::new(s).unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                Variables::default()
+            }),
+            None => Variables::default(),
+        }
+    }
+}
+
+/// Whether a binding is a `bindsym` or `bindcode` binding.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum BindKind {
+    Sym,
+    Code,
+}
+
+impl Display for BindKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BindKind::Sym => write!(f, "bindsym"),
+            BindKind::Code => write!(f, "bindcode"),
+        }
+    }
+}
+
+/// A single key binding, along with where it was defined.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Binding<'a> {
+    pub mode: &'a str,
+    pub kind: BindKind,
+    pub keys: &'a str,
+    pub bind: &'a KeylessBindsym,
+}
+
+impl Display for Binding<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}{} {}", self.kind, self.bind.flags(), self.keys, self.bind.command())
+    }
+}
+
+fn collect_bindings<'a>(bindings: &mut Vec<Binding<'a>>, mode: &'a str,
+                        bindsym: &'a Option<HashMap<String, KeylessBindsym>>,
+                        bindcode: &'a Option<HashMap<String, KeylessBindsym>>) {
+    for (kind, map) in [(BindKind::Sym, bindsym), (BindKind::Code, bindcode)] {
+        if let Some(map) = map {
+            bindings.extend(map.iter().map(|(keys, bind)| Binding { mode, kind, keys, bind }));
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct Modes (HashMap<String, ModeCfg>);
 
 impl Display for Modes {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        for (k, v) in self.0.iter() {
+        let mut modes = self.0.iter().collect::<Vec<(&String, &ModeCfg)>>();
+        modes.sort_by_key(|(k, _)| *k);
+        for (k, v) in modes {
             log::debug!("Converting mode {}...", k);
             let header = format!("# Configuration for mode {}", k);
             write!(f, "{}\nmode {} {{\n{}\n}}\n", header, k, indent(&v.to_string(), 4))?;
@@ -94,7 +283,7 @@ impl Display for Modes {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct ModeCfg {
     // User defined bindsym commands for this mode
@@ -111,7 +300,7 @@ impl Display for ModeCfg {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct Defaults {
     orientation: Option<options::DefaultOrientation>,
@@ -142,28 +331,233 @@ impl Display for Defaults {
     }
 }
 
+/// Floating window and mouse drag settings. These are top-level keys in the config file.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct FloatingCfg {
+    /// Modifier key for dragging (and resizing) floating windows with the mouse
+    #[serde(default)]
+    floating_modifier: Option<floating::FloatingModifier>,
+    /// Minimum size of floating windows
+    #[serde(default)]
+    floating_minimum_size: Option<floating::FloatingSize>,
+    /// Maximum size of floating windows
+    #[serde(default)]
+    floating_maximum_size: Option<floating::FloatingSize>,
+    /// Allow moving tiled windows by dragging them with the floating modifier
+    #[serde(default)]
+    tiling_drag: Option<options::TogglableBool>,
+    /// How far (in pixels) a tiled window has to be dragged before it starts moving
+    #[serde(default)]
+    tiling_drag_threshold: Option<u32>,
+}
+
+impl Display for FloatingCfg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let lines = [
+            self.floating_modifier.as_ref().map(|v| format!("floating_modifier {v}")),
+            self.floating_minimum_size.as_ref().map(|v| format!("floating_minimum_size {v}")),
+            self.floating_maximum_size.as_ref().map(|v| format!("floating_maximum_size {v}")),
+            self.tiling_drag.as_ref().map(|v| format!("tiling_drag {v}")),
+            self.tiling_drag_threshold.map(|v| format!("tiling_drag_threshold {v}")),
+        ];
+        write!(f, "{}", lines.into_iter().flatten().collect::<Vec<String>>().join("\n"))
+    }
+}
+
+/// Focus behavior settings, from the `[focus]` table.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct FocusCfg {
+    /// Focus windows when the mouse moves over them
+    #[serde(default)]
+    follows_mouse: Option<focus::FocusFollowsMouse>,
+    /// Whether moving focus past the edge of a container wraps around to the other side
+    #[serde(default)]
+    wrapping: Option<focus::FocusWrapping>,
+    /// What happens when a window requests to be focused
+    #[serde(default)]
+    on_window_activation: Option<focus::FocusOnWindowActivation>,
+    /// Move the mouse to the focused output or container when focus changes
+    #[serde(default)]
+    mouse_warping: Option<focus::MouseWarping>,
+    /// What happens when a popup opens while a window is fullscreen
+    #[serde(default)]
+    popup_during_fullscreen: Option<focus::PopupDuringFullscreen>,
+}
+
+impl Display for FocusCfg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let lines = [
+            self.follows_mouse.as_ref().map(|v| format!("focus_follows_mouse {v}")),
+            self.wrapping.as_ref().map(|v| format!("focus_wrapping {v}")),
+            self.on_window_activation.as_ref().map(|v| format!("focus_on_window_activation {v}")),
+            self.mouse_warping.as_ref().map(|v| format!("mouse_warping {v}")),
+            self.popup_during_fullscreen.as_ref().map(|v| format!("popup_during_fullscreen {v}")),
+        ];
+        write!(f, "{}", lines.into_iter().flatten().collect::<Vec<String>>().join("\n"))
+    }
+}
+
+/// Border, title bar and font settings. These are top-level keys in the config file.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Appearance {
+    /// Font used for title bars (and swaybar, unless it sets its own)
+    #[serde(default)]
+    font: Option<appearance::Font>,
+    /// Hide borders that touch the edge of the screen
+    #[serde(default)]
+    hide_edge_borders: Option<appearance::HideEdgeBorders>,
+    /// Only draw borders when there is more than one window on a workspace
+    #[serde(default)]
+    smart_borders: Option<appearance::SmartBorders>,
+    /// Thickness of title bar borders, in pixels
+    #[serde(default)]
+    titlebar_border_thickness: Option<u16>,
+    /// Padding around title bar text, in pixels
+    #[serde(default)]
+    titlebar_padding: Option<appearance::TitlebarPadding>,
+    /// Title bar text alignment
+    #[serde(default)]
+    title_align: Option<appearance::TitleAlign>,
+    /// Show marks in title bars
+    #[serde(default)]
+    show_marks: Option<bool>,
+}
+
+impl Display for Appearance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let lines = [
+            self.font.as_ref().map(|v| format!("font {v}")),
+            self.hide_edge_borders.as_ref().map(|v| format!("hide_edge_borders {v}")),
+            self.smart_borders.as_ref().map(|v| format!("smart_borders {v}")),
+            self.titlebar_border_thickness.map(|v| format!("titlebar_border_thickness {v}")),
+            self.titlebar_padding.as_ref().map(|v| format!("titlebar_padding {v}")),
+            self.title_align.as_ref().map(|v| format!("title_align {v}")),
+            self.show_marks.map(|v| format!("show_marks {}", if v { "yes" } else { "no" })),
+        ];
+        write!(f, "{}", lines.into_iter().flatten().collect::<Vec<String>>().join("\n"))
+    }
+}
+
+/// Settings for a single workspace.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct WorkspaceCfg {
+    /// Outputs to put this workspace on, in order of preference
+    #[serde(default)]
+    output: Option<Vec<String>>,
+    /// Gaps for this workspace, overriding the defaults in the `[gaps]` table
+    #[serde(default)]
+    gaps: Option<gaps::Gaps>,
+}
+
+impl WorkspaceCfg {
+    /// Config commands for a workspace, without the leading `workspace <name>`.
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(o) = &self.output {
+            if !o.is_empty() { args.push(format!("output {}", o.join(" "))) }
+        }
+        if let Some(g) = &self.gaps {
+            args.extend(g.args().into_iter().map(|a| format!("gaps {a}")));
+        }
+        args
+    }
+}
+
+/// Client color configuration. Each field corresponds to a `client.<class>` command.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClientCfg {
+    background: Option<String>,
+    focused: Option<client::ClientColors>,
+    focused_inactive: Option<client::ClientColors>,
+    focused_tab_title: Option<client::ClientColors>,
+    placeholder: Option<client::ClientColors>,
+    unfocused: Option<client::ClientColors>,
+    urgent: Option<client::ClientColors>,
+}
+
+impl ClientCfg {
+    fn opts(&self) -> Vec<client::ClientOpts> {
+        use client::ClientOpts;
+        [
+            self.background.clone().map(ClientOpts::Background),
+            self.focused.clone().map(ClientOpts::Focused),
+            self.focused_inactive.clone().map(ClientOpts::FocusedInactive),
+            self.focused_tab_title.clone().map(ClientOpts::FocusedTabTitle),
+            self.placeholder.clone().map(ClientOpts::Placeholder),
+            self.unfocused.clone().map(ClientOpts::Unfocused),
+            self.urgent.clone().map(ClientOpts::Urgent),
+        ].into_iter().flatten().collect()
+    }
+}
+
 /// Bindsym argument structure, minus the keys.
 ///
 /// When assembling the config-level bindsym commands, the bind map will provide the keys / key
 /// codes. This struct provides the rest of the arguments, as well as the runtime command to
 /// execute.
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct KeylessBindsym {
     #[serde(default)]
     flags: ArgMap<bind::Bind>,
+    /// Human-readable description of what this binding does (used for cheat sheets)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Group this binding is listed under in cheat sheets / shortcut helpers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
     #[serde(flatten)]
     command: Runtime
 }
 
 impl KeylessBindsym {
     pub fn new(flags: ArgMap<bind::Bind>, command: Runtime) -> Self {
-        Self { flags, command }
+        Self { flags, description: None, group: None, command }
+    }
+
+    /// Set the description and group of this binding (for cheat sheets).
+    pub fn with_description(mut self, description: &str, group: &str) -> Self {
+        self.description = Some(description.to_string());
+        self.group = Some(group.to_string());
+        self
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The group this binding belongs to. Defaults to the type of command if not specified.
+    pub fn group(&self) -> String {
+        match &self.group {
+            Some(g) => g.clone(),
+            None => RuntimeKind::from(&self.command).to_string(),
+        }
+    }
+
+    /// A comment describing this binding, in the `## group // description // keys ##` format
+    /// that rofi shortcut helpers (such as rofi-i3-shortcut-help) read from Sway / i3 configs.
+    ///
+    /// Bindings without a description don't get a comment.
+    pub fn shortcut_comment(&self, keys: &str) -> Option<String> {
+        self.description.as_ref().map(|d| format!("## {} // {} // {} ##", self.group(), d, keys))
+    }
+
+    pub fn flags(&self) -> &ArgMap<bind::Bind> {
+        &self.flags
+    }
+
+    pub fn command(&self) -> &Runtime {
+        &self.command
     }
 }
 
 /// Arguments for generating swaybars. This may be refactored in a future update.
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct Bar {
     #[serde(default)]
@@ -180,6 +574,7 @@ impl Display for Bar {
     }
 }
 
+#[allow(dead_code)]
 impl Bar {
     fn new(id: String, status_command: String) -> Self {
         Bar{ id, status_command }
@@ -196,14 +591,31 @@ fn with_comment_header(section: String, header: String) -> String {
     format!("{}\n{}\n\n", comment, section)
 }
 
+/// Bindings sorted by their keys, so that the generated config doesn't change between runs.
+fn sorted(bindings: &HashMap<String, KeylessBindsym>) -> impl Iterator<Item = (&String, &KeylessBindsym)> {
+    let mut sorted = bindings.iter().collect::<Vec<(&String, &KeylessBindsym)>>();
+    sorted.sort_by_key(|(k, _)| *k);
+    sorted.into_iter()
+}
+
 fn stringify_sets(sets: &Option<HashMap<String, String>>) -> String {
     log::debug!("Converting set commands...");
     match sets {
         Some(s) => {
             if s.is_empty() {String::new()}
             else {
-                with_comment_header(s.iter().map(|(k, v)|
-                    format!("set ${k} {v}")
+                // variables have to be defined before they are referenced by other variables
+                let order = match Variables::new(s) {
+                    Ok(vars) => vars.order().to_vec(),
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        let mut keys = s.keys().cloned().collect::<Vec<String>>();
+                        keys.sort();
+                        keys
+                    }
+                };
+                with_comment_header(order.iter().map(|k|
+                    format!("set ${k} {}", s[k])
                 ).collect::<Vec<String>>().join("\n"),
                 "Variables (using [set] table)".to_string())
             }
@@ -228,6 +640,74 @@ fn stringify_defaults (defaults: &Option<Defaults>) -> String {
     }
 }
 
+fn stringify_appearance(appearance: &Appearance) -> String {
+    log::debug!("Converting appearance settings...");
+    let res0 = appearance.to_string();
+    if res0.is_empty() {String::new()}
+    else {
+        with_comment_header(res0, "Borders, title bars and fonts".to_string())
+    }
+}
+
+fn stringify_floating(floating: &FloatingCfg) -> String {
+    log::debug!("Converting floating window settings...");
+    let res0 = floating.to_string();
+    if res0.is_empty() {String::new()}
+    else {
+        with_comment_header(res0, "Floating windows and mouse dragging".to_string())
+    }
+}
+
+fn stringify_focus(focus: &Option<FocusCfg>) -> String {
+    log::debug!("Converting focus settings...");
+    match focus {
+        Some(c) => {
+            let res0 = c.to_string();
+            if res0.is_empty() {String::new()}
+            else {
+                with_comment_header(res0, "Focus behavior (using [focus] table)".to_string())
+            }
+        }
+        None => String::new()
+    }
+}
+
+fn stringify_gaps(gaps: &Option<gaps::Gaps>, smart_gaps: &Option<gaps::SmartGaps>) -> String {
+    log::debug!("Converting gaps...");
+    let mut lines = match gaps {
+        Some(g) => g.args().into_iter().map(|a| format!("gaps {a}")).collect::<Vec<String>>(),
+        None => Vec::new(),
+    };
+    if let Some(s) = smart_gaps {
+        lines.push(format!("smart_gaps {s}"));
+    }
+    if lines.is_empty() {String::new()}
+    else {
+        with_comment_header(lines.join("\n"), "Gaps (using [gaps] table and smart-gaps)".to_string())
+    }
+}
+
+fn stringify_workspaces(workspaces: &Option<HashMap<String, WorkspaceCfg>>, auto_back_and_forth: Option<bool>) -> String {
+    log::debug!("Converting workspace settings...");
+    let auto_back_and_forth = auto_back_and_forth
+        .map(|b| format!("workspace_auto_back_and_forth {}", if b { "yes" } else { "no" }));
+    let mut workspaces: Vec<(&String, &WorkspaceCfg)> = match workspaces {
+        Some(w) => w.iter().collect(),
+        None => Vec::new(),
+    };
+    workspaces.sort_by_key(|(name, _)| *name);
+    let lines = auto_back_and_forth.into_iter().chain(workspaces.into_iter()
+        .flat_map(|(name, ws)| {
+            let name = if name.contains(char::is_whitespace) { format!("\"{name}\"") } else { name.clone() };
+            ws.args().into_iter().map(move |a| format!("workspace {name} {a}"))
+        }))
+        .collect::<Vec<String>>();
+    if lines.is_empty() {String::new()}
+    else {
+        with_comment_header(lines.join("\n"), "Workspace settings (using [workspace] table)".to_string())
+    }
+}
+
 fn stringify_bindsyms(bindsym: &Option<HashMap<String, KeylessBindsym>>) -> String {
     log::debug!("Converting bindsyms...");
     match bindsym {
@@ -235,9 +715,10 @@ fn stringify_bindsyms(bindsym: &Option<HashMap<String, KeylessBindsym>>) -> Stri
             if s.is_empty() {String::new()}
             else {
                 with_comment_header(
-                    s.iter().map(|(k, KeylessBindsym{flags, command})|
-                        format!("bindsym {flags}{k} {command}")
-                    ).collect::<Vec<String>>().join("\n"),
+                    sorted(s).map(|(k, b)| match b.shortcut_comment(k) {
+                        Some(c) => format!("{c}\nbindsym {}{k} {}", b.flags, b.command),
+                        None => format!("bindsym {}{k} {}", b.flags, b.command),
+                    }).collect::<Vec<String>>().join("\n"),
                     "User-defined bindsym commands (using [bindsym] table)".to_string()
                 )
             }
@@ -253,9 +734,10 @@ fn stringify_bindcodes(bindcode: &Option<HashMap<String, KeylessBindsym>>) -> St
             if s.is_empty() {String::new()}
             else {
                 with_comment_header(
-                    s.iter().map(|(k, KeylessBindsym{flags, command})|
-                        format!("bindcode {flags}{k} {command}")
-                    ).collect::<Vec<String>>().join("\n"),
+                    sorted(s).map(|(k, b)| match b.shortcut_comment(k) {
+                        Some(c) => format!("{c}\nbindcode {}{k} {}", b.flags, b.command),
+                        None => format!("bindcode {}{k} {}", b.flags, b.command),
+                    }).collect::<Vec<String>>().join("\n"),
                     "User-defined bindcode commands (using [bindcode] table)".to_string()
                 )
             }
@@ -306,6 +788,32 @@ fn stringify_bar (bar: &Option<Bar>) -> String {
     }
 }
 
+fn stringify_commands (commands: Vec<Runtime>, header: &str) -> String {
+    if commands.is_empty() {String::new()}
+    else {
+        with_comment_header(
+            commands.iter().map(|c| c.to_string()).collect::<Vec<String>>().join("\n"),
+            header.to_string()
+        )
+    }
+}
+
+fn stringify_includes (include: &Option<Vec<options::FilePath>>) -> String {
+    log::debug!("Converting includes...");
+    match include {
+        Some(i) => {
+            if i.is_empty() {String::new()}
+            else {
+                with_comment_header(
+                    i.iter().map(|p| format!("include {p}")).collect::<Vec<String>>().join("\n"),
+                    "Included config files (using include array)".to_string()
+                )
+            }
+        }
+        None => String::new()
+    }
+}
+
 fn stringify_modes (modes: &Option<Modes>) -> String {
     log::debug!("Converting modes...");
     match modes {
@@ -322,16 +830,24 @@ impl Display for Config {
             \nwill need to run `sway -c [config file] -C` to do so.\
             \n\
             \nFor more information, please visit https://github.com/cptlobster/swayconf.";
-        write!(f, "{}{}{}{}{}{}{}{}{}",
+        write!(f, "{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
                with_comment_header(String::new(), header.to_string()),
                stringify_sets(&self.set),
                stringify_exec(&self.exec),
                stringify_exec_always(&self.exec_always),
                stringify_defaults(&self.default),
+               stringify_appearance(&self.appearance),
+               stringify_floating(&self.floating),
+               stringify_focus(&self.focus),
+               stringify_gaps(&self.gaps, &self.smart_gaps),
+               stringify_workspaces(&self.workspace, self.workspace_auto_back_and_forth),
+               stringify_commands(self.output_commands(), "Output configuration (using [output] table)"),
+               stringify_commands(self.client_commands(), "Client colors (using [client] table)"),
                stringify_modes(&self.modes),
                stringify_bindsyms(&self.bindsym),
                stringify_bindcodes(&self.bindcode),
-               stringify_bar(&self.bar)
+               stringify_bar(&self.bar),
+               stringify_includes(&self.include)
         )
     }
 }
@@ -339,16 +855,19 @@ impl Display for Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sway::analysis;
 
     #[test]
     fn test_to_sway() {
-        let mut config = Config::default();
-        config.exec = Some(
-            vec![
-                exec::ExecParams::String("ls".to_string()),
-                exec::ExecParams::String("/bin/bash".to_string()),
-            ]
-        );
+        let mut config = Config {
+            exec: Some(
+                vec![
+                    exec::ExecParams::String("ls".to_string()),
+                    exec::ExecParams::String("/bin/bash".to_string()),
+                ]
+            ),
+            ..Default::default()
+        };
 
         let mut keys = HashMap::new();
         keys.insert("Mod4+Shift".to_string(), KeylessBindsym::new(ArgMap::<bind::Bind>::default(), Runtime::Exec(exec::ExecParams::String("ls -la ~".to_string()))));
@@ -363,6 +882,183 @@ mod tests {
         println!("{}", &config.to_string());
     }
 
+    #[test]
+    fn test_shortcut_comments() {
+        let cfg: Config = toml::from_str("[bindsym]\
+            \n\"$mod+Return\" = { exec = \"alacritty\", description = \"Open a terminal\", group = \"Launch\" }\
+            \n\"$mod+q\" = { kill = {}, description = \"Close window\" }").unwrap();
+        let out = cfg.to_string();
+        assert!(out.contains("## Launch // Open a terminal // $mod+Return ##\nbindsym $mod+Return exec alacritty"));
+        assert!(out.contains("## kill // Close window // $mod+q ##\nbindsym $mod+q kill"));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let cfg: Config = toml::from_str("smart-gaps = \"inverse-outer\"\
+            \n[gaps]\
+            \ninner = 10\
+            \nouter = -5\
+            \n[workspace.\"2 web\".gaps]\
+            \ntop = 20\
+            \n[bindsym]\
+            \n\"$mod+g\".gaps = { type = \"inner\", scope = \"all\", change = \"plus\", amount = 5 }\
+            \n\"$mod+Shift+g\".gaps = { type = \"outer\", amount = 0 }").unwrap();
+        let out = cfg.to_string();
+        assert!(out.contains("gaps inner 10\ngaps outer -5\nsmart_gaps inverse_outer\n"));
+        assert!(out.contains("workspace \"2 web\" gaps top 20\n"));
+        assert!(out.contains("bindsym $mod+g gaps inner all plus 5"));
+        assert!(out.contains("bindsym $mod+Shift+g gaps outer current set 0"));
+
+        let cfg: Config = toml::from_str("smart-gaps = \"toggle\"").unwrap();
+        assert!(cfg.to_string().contains("smart_gaps toggle"));
+    }
+
+    #[test]
+    fn test_workspaces() {
+        let cfg: Config = toml::from_str("workspace-auto-back-and-forth = true\
+            \n[workspace.1]\
+            \noutput = [\"DP-1\", \"eDP-1\"]\
+            \n[workspace.10]\
+            \noutput = [\"HDMI-A-1\"]\
+            \n[bindsym]\
+            \n\"$mod+1\".workspace = 1\
+            \n\"$mod+2\".workspace = { number = 2 }\
+            \n\"$mod+3\".workspace = { number = 3, name = \"web\" }\
+            \n\"$mod+Tab\".workspace = \"next-on-output\"\
+            \n\"$mod+grave\".workspace = { target = \"back-and-forth\", no-auto-back-and-forth = true }").unwrap();
+        let out = cfg.to_string();
+        assert!(out.contains("workspace_auto_back_and_forth yes\
+            \nworkspace 1 output DP-1 eDP-1\
+            \nworkspace 10 output HDMI-A-1\n"));
+        assert!(out.contains("bindsym $mod+1 workspace 1\n"));
+        assert!(out.contains("bindsym $mod+2 workspace number 2\n"));
+        assert!(out.contains("bindsym $mod+3 workspace number 3 web\n"));
+        assert!(out.contains("bindsym $mod+Tab workspace next_on_output\n"));
+        assert!(out.contains("bindsym $mod+grave workspace --no-auto-back-and-forth back_and_forth\n"));
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut cfg: Config = toml::from_str("[autogen.marks]\
+            \nkeys = \"ab\"\
+            \nset = \"$mod+m\"\
+            \njump = \"$mod+apostrophe\"\
+            \n[bindsym]\
+            \n\"$mod+u\".unmark = {}\
+            \n\"$mod+Shift+u\".unmark = \"a\"\
+            \n\"$mod+t\".mark = { name = \"t\", mode = \"replace\", toggle = true }").unwrap();
+        assert!(cfg.expand_autogen().is_empty());
+        let out = cfg.to_string();
+        assert!(out.contains("bindsym $mod+m mode mark\n"));
+        assert!(out.contains("bindsym $mod+apostrophe mode goto\n"));
+        assert!(out.contains("mode mark {"));
+        assert!(out.contains("    bindsym a mark --add a; mode default\n"));
+        assert!(out.contains("mode goto {"));
+        assert!(out.contains("    bindsym b [con_mark=\"b\"] focus; mode default\n"));
+        assert!(out.contains("    bindsym Escape mode default\n"));
+        assert!(out.contains("bindsym $mod+u unmark\n"));
+        assert!(out.contains("bindsym $mod+Shift+u unmark a\n"));
+        assert!(out.contains("bindsym $mod+t mark --replace --toggle t\n"));
+        assert!(analysis::binding_conflicts(&cfg).is_empty());
+    }
+
+    #[test]
+    fn test_mark_keys() {
+        let mut cfg: Config = toml::from_str("[autogen.marks]\
+            \nkeys = \"a1';\"\
+            \nset = \"$mod+m\"\
+            \njump = \"$mod+apostrophe\"\
+            \n[bindsym]\
+            \n\"$mod+m\".exec = \"true\"\
+            \n[modes.goto.bindsym]\
+            \nEscape.exec = \"true\"").unwrap();
+        let conflicts: Vec<String> = cfg.expand_autogen().iter().map(|c| c.to_string()).collect();
+        assert_eq!(conflicts, vec![
+            "bindsym $mod+m in mode default replaces a binding generated by autogen ($mod+m)",
+            "bindsym Escape in mode goto replaces a binding generated by autogen (Escape)",
+        ]);
+        let out = cfg.to_string();
+        assert!(out.contains("bindsym $mod+m exec true\n"));
+        assert!(out.contains("    bindsym 1 mark --add 1; mode default\n"));
+        assert!(out.contains("    bindsym apostrophe mark --add apostrophe; mode default\n"));
+        assert!(out.contains("    bindsym semicolon [con_mark=\"semicolon\"] focus; mode default\n"));
+        assert!(out.contains("    bindsym Escape exec true\n"));
+
+        let err = toml::from_str::<Config>("[autogen.marks]\nkeys = \"ab \"\nset = \"m\"\njump = \"g\"");
+        assert!(err.unwrap_err().to_string().contains("' ' can't be used as a mark key"));
+    }
+
+    #[test]
+    fn test_output_scale() {
+        let cfg: Config = toml::from_str("[output.eDP-1]\nscale = 1.25").unwrap();
+        assert!(cfg.to_string().contains("output eDP-1 scale 1.25\n"));
+        assert!(toml::from_str::<Config>("[output.eDP-1]\nscale = 0").is_err());
+        assert!(toml::from_str::<Config>("[output.eDP-1]\nscale = nan").is_err());
+        assert!(toml::from_str::<Config>("[output.eDP-1]\nscale = -inf").is_err());
+    }
+
+    #[test]
+    fn test_appearance() {
+        let cfg: Config = toml::from_str("hide-edge-borders = { borders = \"smart-no-gaps\", i3 = true }\
+            \nsmart-borders = \"no-gaps\"\
+            \ntitlebar-border-thickness = 2\
+            \ntitlebar-padding = { horizontal = 5, vertical = 1 }\
+            \ntitle-align = \"center\"\
+            \nshow-marks = false\
+            \n[font]\
+            \npango = \"Fira Code\"\
+            \nsize = 12").unwrap();
+        assert!(cfg.to_string().contains("# Borders, title bars and fonts\
+            \nfont pango:Fira Code 12\
+            \nhide_edge_borders --i3 smart_no_gaps\
+            \nsmart_borders no_gaps\
+            \ntitlebar_border_thickness 2\
+            \ntitlebar_padding 5 1\
+            \ntitle_align center\
+            \nshow_marks no\n"));
+
+        let cfg: Config = toml::from_str("hide-edge-borders = \"both\"\nsmart-borders = true").unwrap();
+        assert!(cfg.to_string().contains("hide_edge_borders both\nsmart_borders on\n"));
+    }
+
+    #[test]
+    fn test_floating() {
+        let cfg: Config = toml::from_str("floating-modifier = { modifier = \"$mod\", mode = \"inverse\" }\
+            \nfloating-minimum-size = { width = 75, height = 50 }\
+            \nfloating-maximum-size = { width = -1, height = -1 }\
+            \ntiling-drag = \"toggle\"\
+            \ntiling-drag-threshold = 9\
+            \n[bindsym]\
+            \n\"$mod+m\".floating-modifier = \"Mod1\"").unwrap();
+        let out = cfg.to_string();
+        assert!(out.contains("# Floating windows and mouse dragging\
+            \nfloating_modifier $mod inverse\
+            \nfloating_minimum_size 75 x 50\
+            \nfloating_maximum_size -1 x -1\
+            \ntiling_drag toggle\
+            \ntiling_drag_threshold 9\n"));
+        assert!(out.contains("bindsym $mod+m floating_modifier Mod1"));
+
+        let cfg: Config = toml::from_str("floating-modifier = \"$mod\"\ntiling-drag = true").unwrap();
+        assert!(cfg.to_string().contains("floating_modifier $mod\ntiling_drag enable\n"));
+    }
+
+    #[test]
+    fn test_focus() {
+        let cfg: Config = toml::from_str("[focus]\
+            \nfollows-mouse = \"always\"\
+            \nwrapping = \"workspace\"\
+            \non-window-activation = \"urgent\"\
+            \nmouse-warping = \"container\"\
+            \npopup-during-fullscreen = \"leave-fullscreen\"").unwrap();
+        assert!(cfg.to_string().contains("# Focus behavior (using [focus] table)\
+            \nfocus_follows_mouse always\
+            \nfocus_wrapping workspace\
+            \nfocus_on_window_activation urgent\
+            \nmouse_warping container\
+            \npopup_during_fullscreen leave_fullscreen\n"));
+    }
+
     #[test]
     fn test_serde() {
         let cfg: Config = toml::from_str(
@@ -377,6 +1073,6 @@ mod tests {
             \n\"$mod+X\".exec.command = \"~/beans.sh\""
         ).unwrap();
 
-        println!("{}", cfg.to_string());
+        println!("{}", cfg);
     }
 }
\ No newline at end of file
@@ -15,6 +15,7 @@
 //
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use strum::Display;
 
@@ -22,7 +23,7 @@ use strum::Display;
 ///
 /// A [Vec] would normally suffice for our purposes, but this struct implements [Display],
 /// [Default], and [Serialize]/[Deserialize] traits to be compatible with everything else.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct CriteriaVec(Vec<Criteria>);
 
@@ -35,12 +36,7 @@ impl FmtDisplay for CriteriaVec {
     }
 }
 
-impl Default for CriteriaVec {
-    fn default() -> Self {
-        Self(Vec::new())
-    }
-}
-
+#[allow(dead_code)]
 impl CriteriaVec {
     pub fn new() -> Self {
         Self::default()
@@ -55,7 +51,7 @@ impl CriteriaVec {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Criteria {
@@ -89,7 +85,7 @@ pub enum Criteria {
     Workspace(String)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum ShellType {
@@ -101,7 +97,8 @@ pub enum ShellType {
     Focused
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum UrgentState {
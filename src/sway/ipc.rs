@@ -0,0 +1,347 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+// This mirrors the message types and replies of sway-ipc(7) as a whole, so that new commands don't
+// have to extend the protocol first; the binary only uses part of it.
+#![allow(dead_code)]
+
+use std::io::{Error as IoError, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::Error as JsonError;
+use thiserror::Error;
+
+/// Every IPC message starts with these bytes.
+const MAGIC: &[u8; 6] = b"i3-ipc";
+
+/// Errors that can occur while talking to Sway.
+#[derive(Debug, Error)]
+pub enum IpcError {
+    #[error("Neither SWAYSOCK nor I3SOCK is set, is Sway running?")]
+    NoSocket,
+    #[error("I/O Error: {0}")]
+    Io(#[from] IoError),
+    #[error("Invalid reply: {0}")]
+    Json(#[from] JsonError),
+    #[error("Invalid reply: bad magic string")]
+    BadMagic,
+    #[error("Invalid reply: expected message type {expected}, got {got}")]
+    UnexpectedReply { expected: u32, got: u32 },
+}
+
+/// IPC message types used by swayconf. See sway-ipc(7) for the full list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MessageType {
+    RunCommand = 0,
+    GetOutputs = 3,
+    GetTree = 4,
+    GetVersion = 7,
+    GetConfig = 9,
+    GetInputs = 100,
+}
+
+/// The result of a single command sent with `RUN_COMMAND`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub success: bool,
+    /// Whether the command failed because it could not be parsed
+    #[serde(default)]
+    pub parse_error: bool,
+    /// Human-readable error message, if the command failed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Reply to `GET_VERSION`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    pub major: i64,
+    pub minor: i64,
+    pub patch: i64,
+    pub human_readable: String,
+    pub loaded_config_file_name: String,
+}
+
+/// Reply to `GET_CONFIG`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ConfigReply {
+    config: String,
+}
+
+/// A rectangle, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+/// An output mode (resolution and refresh rate).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputMode {
+    pub width: i64,
+    pub height: i64,
+    /// Refresh rate, in mHz
+    pub refresh: i64,
+}
+
+/// An entry in the reply to `GET_OUTPUTS`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub name: String,
+    #[serde(default)]
+    pub make: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub serial: String,
+    pub active: bool,
+    #[serde(default)]
+    pub scale: Option<f64>,
+    #[serde(default)]
+    pub transform: Option<String>,
+    #[serde(default)]
+    pub current_workspace: Option<String>,
+    #[serde(default)]
+    pub modes: Vec<OutputMode>,
+    #[serde(default)]
+    pub current_mode: Option<OutputMode>,
+    #[serde(default)]
+    pub rect: Rect,
+}
+
+/// An entry in the reply to `GET_INPUTS`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+    pub identifier: String,
+    pub name: String,
+    #[serde(default)]
+    pub vendor: i64,
+    #[serde(default)]
+    pub product: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub xkb_active_layout_name: Option<String>,
+    /// libinput settings, if this is a libinput device
+    #[serde(default)]
+    pub libinput: Option<serde_json::Value>,
+}
+
+/// A node in the reply to `GET_TREE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    pub id: i64,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub layout: Option<String>,
+    #[serde(default)]
+    pub rect: Rect,
+    #[serde(default)]
+    pub focused: bool,
+    #[serde(default)]
+    pub urgent: bool,
+    #[serde(default)]
+    pub marks: Vec<String>,
+    #[serde(default)]
+    pub app_id: Option<String>,
+    #[serde(default)]
+    pub pid: Option<i64>,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub floating_nodes: Vec<Node>,
+}
+
+impl Node {
+    /// Iterate over this node and all of its descendants (including floating nodes).
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &Node> + '_> {
+        Box::new(std::iter::once(self)
+            .chain(self.nodes.iter().flat_map(|n| n.iter()))
+            .chain(self.floating_nodes.iter().flat_map(|n| n.iter())))
+    }
+}
+
+/// A connection to Sway's IPC socket.
+#[derive(Debug)]
+pub struct Connection {
+    stream: UnixStream,
+}
+
+impl Connection {
+    /// Connect to the socket specified by `$SWAYSOCK` (or `$I3SOCK`).
+    pub fn connect() -> Result<Self, IpcError> {
+        Self::connect_to(&socket_path()?)
+    }
+
+    /// Connect to the socket at the specified path.
+    pub fn connect_to(path: &Path) -> Result<Self, IpcError> {
+        log::debug!("Connecting to IPC socket {}", path.display());
+        Ok(Connection { stream: UnixStream::connect(path)? })
+    }
+
+    fn send(&mut self, kind: MessageType, payload: &str) -> Result<(), IpcError> {
+        let mut message = Vec::with_capacity(MAGIC.len() + 8 + payload.len());
+        message.extend_from_slice(MAGIC);
+        message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+        message.extend_from_slice(&(kind as u32).to_ne_bytes());
+        message.extend_from_slice(payload.as_bytes());
+        self.stream.write_all(&message)?;
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<(u32, Vec<u8>), IpcError> {
+        let mut header = [0u8; 14];
+        self.stream.read_exact(&mut header)?;
+        if &header[..6] != MAGIC { return Err(IpcError::BadMagic) }
+        let len = u32::from_ne_bytes(header[6..10].try_into().unwrap());
+        let kind = u32::from_ne_bytes(header[10..14].try_into().unwrap());
+        let mut payload = vec![0u8; len as usize];
+        self.stream.read_exact(&mut payload)?;
+        Ok((kind, payload))
+    }
+
+    fn request<T: DeserializeOwned>(&mut self, kind: MessageType, payload: &str) -> Result<T, IpcError> {
+        self.send(kind, payload)?;
+        let (got, reply) = self.receive()?;
+        if got != kind as u32 { return Err(IpcError::UnexpectedReply { expected: kind as u32, got }) }
+        Ok(serde_json::from_slice(&reply)?)
+    }
+
+    /// Run one or more commands (separated by `;` or newlines). Returns one result per command.
+    pub fn run_command(&mut self, command: &str) -> Result<Vec<CommandResult>, IpcError> {
+        self.request(MessageType::RunCommand, command)
+    }
+
+    pub fn get_version(&mut self) -> Result<Version, IpcError> {
+        self.request(MessageType::GetVersion, "")
+    }
+
+    /// Get the contents of the config file that Sway last loaded.
+    pub fn get_config(&mut self) -> Result<String, IpcError> {
+        self.request::<ConfigReply>(MessageType::GetConfig, "").map(|r| r.config)
+    }
+
+    pub fn get_outputs(&mut self) -> Result<Vec<Output>, IpcError> {
+        self.request(MessageType::GetOutputs, "")
+    }
+
+    pub fn get_inputs(&mut self) -> Result<Vec<Input>, IpcError> {
+        self.request(MessageType::GetInputs, "")
+    }
+
+    pub fn get_tree(&mut self) -> Result<Node, IpcError> {
+        self.request(MessageType::GetTree, "")
+    }
+}
+
+fn socket_path() -> Result<PathBuf, IpcError> {
+    std::env::var_os("SWAYSOCK")
+        .or_else(|| std::env::var_os("I3SOCK"))
+        .map(PathBuf::from)
+        .ok_or(IpcError::NoSocket)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A fake Sway IPC server. It accepts a single connection and answers each request with the
+    /// next reply in `replies` (as `(message type, payload)`), recording the requests it got.
+    pub fn fake_server(name: &str, replies: Vec<(u32, String)>) -> (PathBuf, JoinHandle<Vec<(u32, String)>>) {
+        let path = std::env::temp_dir().join(format!("swayconf-ipc-{}-{}.sock", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut requests = Vec::new();
+            for (kind, reply) in replies {
+                let mut header = [0u8; 14];
+                if stream.read_exact(&mut header).is_err() { break }
+                assert_eq!(&header[..6], MAGIC);
+                let len = u32::from_ne_bytes(header[6..10].try_into().unwrap());
+                let mut payload = vec![0u8; len as usize];
+                stream.read_exact(&mut payload).unwrap();
+                requests.push((u32::from_ne_bytes(header[10..14].try_into().unwrap()), String::from_utf8(payload).unwrap()));
+
+                let mut message = MAGIC.to_vec();
+                message.extend_from_slice(&(reply.len() as u32).to_ne_bytes());
+                message.extend_from_slice(&kind.to_ne_bytes());
+                message.extend_from_slice(reply.as_bytes());
+                stream.write_all(&message).unwrap();
+            }
+            requests
+        });
+        (path, handle)
+    }
+
+    #[test]
+    fn test_run_command() {
+        let (path, server) = fake_server("run", vec![(0, "[{\"success\": true}, \
+            {\"success\": false, \"parse_error\": true, \"error\": \"Unknown/invalid command 'foo'\"}]".to_string())]);
+        let mut conn = Connection::connect_to(&path).unwrap();
+        let results = conn.run_command("reload; foo").unwrap();
+        assert_eq!(results, vec![
+            CommandResult { success: true, parse_error: false, error: None },
+            CommandResult { success: false, parse_error: true, error: Some("Unknown/invalid command 'foo'".to_string()) },
+        ]);
+        assert_eq!(server.join().unwrap(), vec![(0, "reload; foo".to_string())]);
+    }
+
+    #[test]
+    fn test_queries() {
+        let (path, server) = fake_server("queries", vec![
+            (7, "{\"human_readable\": \"1.10\", \"major\": 1, \"minor\": 10, \"patch\": 0, \
+                \"loaded_config_file_name\": \"/home/me/.config/sway/config\"}".to_string()),
+            (9, "{\"config\": \"set $mod Mod4\\n\"}".to_string()),
+            (3, "[{\"name\": \"eDP-1\", \"active\": true, \"scale\": 1.5, \"current_workspace\": \"1\", \
+                \"rect\": {\"x\": 0, \"y\": 0, \"width\": 1920, \"height\": 1080}}]".to_string()),
+            (100, "[{\"identifier\": \"1:1:AT_Translated_Set_2_keyboard\", \"name\": \"AT Translated Set 2 keyboard\", \
+                \"vendor\": 1, \"product\": 1, \"type\": \"keyboard\", \"xkb_active_layout_name\": \"English (US)\"}]".to_string()),
+            (4, "{\"id\": 1, \"name\": \"root\", \"type\": \"root\", \"nodes\": [{\"id\": 2, \"type\": \"output\", \
+                \"nodes\": [{\"id\": 3, \"type\": \"con\", \"app_id\": \"foot\", \"marks\": [\"a\"], \"focused\": true}]}]}".to_string()),
+        ]);
+        let mut conn = Connection::connect_to(&path).unwrap();
+        let version = conn.get_version().unwrap();
+        assert_eq!((version.major, version.minor), (1, 10));
+        assert_eq!(conn.get_config().unwrap(), "set $mod Mod4\n");
+        let outputs = conn.get_outputs().unwrap();
+        assert_eq!(outputs[0].name, "eDP-1");
+        assert_eq!(outputs[0].rect.width, 1920);
+        assert_eq!(conn.get_inputs().unwrap()[0].kind, "keyboard");
+        let tree = conn.get_tree().unwrap();
+        let focused = tree.iter().find(|n| n.focused).unwrap();
+        assert_eq!(focused.app_id.as_deref(), Some("foot"));
+        assert_eq!(server.join().unwrap().iter().map(|(k, _)| *k).collect::<Vec<u32>>(), vec![7, 9, 3, 100, 4]);
+    }
+
+    #[test]
+    fn test_unexpected_reply() {
+        let (path, server) = fake_server("unexpected", vec![(9, "{\"config\": \"\"}".to_string())]);
+        let mut conn = Connection::connect_to(&path).unwrap();
+        assert!(matches!(conn.get_version(), Err(IpcError::UnexpectedReply { expected: 7, got: 9 })));
+        server.join().unwrap();
+    }
+}
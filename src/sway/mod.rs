@@ -29,7 +29,15 @@ pub mod options;
 /// This has a rigid structure for config-only commands, so that [serde] can assemble/disassemble
 /// TOML in a way that is even moderately comprehensible.
 pub mod config;
+/// Sway config variables (`set`) and their resolution.
+pub mod vars;
+/// Client for Sway's IPC protocol (see sway-ipc(7)).
+pub mod ipc;
+/// Static analysis of configs (such as conflicting key bindings).
+pub mod analysis;
+/// Parsing Sway config files back into comparable statements.
+pub mod parse;
 /// Criteria generation.
 mod criteria;
 /// Autogen complex structures.
-mod autogen;
\ No newline at end of file
+pub mod autogen;
\ No newline at end of file
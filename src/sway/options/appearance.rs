@@ -0,0 +1,137 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+
+/// Which window borders to hide when they touch the edge of the screen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum EdgeBorders {
+    None,
+    Vertical,
+    Horizontal,
+    Both,
+    /// Hide borders if there is only one window on the workspace
+    Smart,
+    /// Like `smart`, but only if gaps are disabled
+    #[serde(alias = "smart_no_gaps")]
+    SmartNoGaps,
+}
+
+/// Options for the `hide_edge_borders` command. Either just the borders to hide
+/// (`hide-edge-borders = "smart"`), or a table that also sets the `--i3` flag
+/// (`hide-edge-borders = { borders = "both", i3 = true }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum HideEdgeBorders {
+    Borders(EdgeBorders),
+    Flags {
+        borders: EdgeBorders,
+        /// Hide the title bar of a single tabbed / stacked window too, like i3 does
+        #[serde(default)]
+        i3: bool,
+    },
+}
+
+impl FmtDisplay for HideEdgeBorders {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            HideEdgeBorders::Borders(b) | HideEdgeBorders::Flags { borders: b, i3: false } => write!(f, "{b}"),
+            HideEdgeBorders::Flags { borders, i3: true } => write!(f, "--i3 {borders}"),
+        }
+    }
+}
+
+/// Options for the `smart_borders` command: `true` / `false`, or `no-gaps` to only draw borders
+/// when gaps are disabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum SmartBorders {
+    Enabled(bool),
+    NoGaps(NoGaps),
+}
+
+/// The `no_gaps` setting of [SmartBorders].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NoGaps {
+    #[serde(alias = "no_gaps")]
+    NoGaps,
+}
+
+impl FmtDisplay for SmartBorders {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            SmartBorders::Enabled(true) => write!(f, "on"),
+            SmartBorders::Enabled(false) => write!(f, "off"),
+            SmartBorders::NoGaps(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Padding around title bar text, in pixels. If `vertical` is not set, `horizontal` is used for
+/// both.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct TitlebarPadding {
+    horizontal: u16,
+    #[serde(default)]
+    vertical: Option<u16>,
+}
+
+impl FmtDisplay for TitlebarPadding {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.vertical {
+            Some(v) => write!(f, "{} {}", self.horizontal, v),
+            None => write!(f, "{}", self.horizontal),
+        }
+    }
+}
+
+/// Title bar text alignment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TitleAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// A Pango font description, such as `{ pango = "Fira Code", size = 12 }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Font {
+    /// Font family (and optionally style, such as "Fira Code Bold")
+    pango: String,
+    /// Font size, in points
+    #[serde(default)]
+    size: Option<u16>,
+}
+
+impl FmtDisplay for Font {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.size {
+            Some(s) => write!(f, "pango:{} {}", self.pango, s),
+            None => write!(f, "pango:{}", self.pango),
+        }
+    }
+}
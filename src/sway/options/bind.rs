@@ -16,10 +16,11 @@
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
 
 /// Flags for bindsym commands.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema, Hash)]
 #[serde(rename_all = "kebab-case")]
 pub enum Bind {
     #[strum(to_string = "--whole-window")]
@@ -48,7 +49,7 @@ pub enum Bind {
 /// 
 /// This exists mainly to provide [Display] support (similar to the [ArgList] struct), except
 /// instead of joining everything with spaces it joins them with `+`.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct BindKeys(Vec<String>);
 
@@ -58,12 +59,7 @@ impl Display for BindKeys {
     }
 }
 
-impl Default for BindKeys {
-    fn default() -> Self {
-        BindKeys(Vec::new())
-    }
-}
-
+#[allow(dead_code)]
 impl BindKeys {
     pub fn new() -> Self {
         BindKeys::default()
@@ -72,13 +68,22 @@ impl BindKeys {
     pub fn from(vec: Vec<String>) -> Self {
         BindKeys(vec)
     }
+
+    /// Split a key combination (such as `$mod+Shift+q`) into its individual keys.
+    pub fn parse(keys: &str) -> Self {
+        BindKeys(keys.split('+').map(|k| k.to_string()).collect())
+    }
+
+    pub fn keys(&self) -> &[String] {
+        &self.0
+    }
 }
 
 /// Key sequence for bindcode commands.
 ///
 /// This exists mainly to provide [Display] support (similar to the [ArgList] struct), except
 /// instead of joining everything with spaces it joins them with `+`.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct BindCodes(Vec<u8>);
 
@@ -88,12 +93,7 @@ impl Display for BindCodes {
     }
 }
 
-impl Default for BindCodes {
-    fn default() -> Self {
-        BindCodes(Vec::new())
-    }
-}
-
+#[allow(dead_code)]
 impl BindCodes {
     pub fn new() -> Self {
         BindCodes::default()
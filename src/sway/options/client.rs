@@ -15,31 +15,43 @@
 //
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use strum::Display as StrumDisplay;
 
 /// Configure colors of window borders and title bars
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, StrumDisplay)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
 pub enum ClientOpts {
     /// Ignored. Only present for i3 compatibility.
+    #[strum(to_string = "background {0}")]
     Background(String),
     /// The window that has focus
+    #[strum(to_string = "focused {0}")]
     Focused(ClientColors),
     /// The most recently focused window in a container which isn't focused
+    #[strum(to_string = "focused_inactive {0}")]
     FocusedInactive(ClientColors),
     /// A view that has a focused descendant container
+    #[strum(to_string = "focused_tab_title {0}")]
     FocusedTabTitle(ClientColors),
     /// Ignored. Only present for i3 compatibility.
+    #[strum(to_string = "placeholder {0}")]
     Placeholder(ClientColors),
     /// A view that does not have focus
+    #[strum(to_string = "unfocused {0}")]
     Unfocused(ClientColors),
     /// A view with an urgency hint.
     /// 
     /// *Note: Since native Wayland windows do not support urgency, this only works for XWayland
     /// windows.*
+    #[strum(to_string = "urgent {0}")]
     Urgent(ClientColors)
 }
 
 /// All color groups for client classes
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct ClientColors {
     /// The border around the title bar
@@ -49,8 +61,10 @@ pub struct ClientColors {
     /// The text color of the title bar
     text: String,
     /// The color used to indicate where new views will open
+    #[serde(default)]
     indicator: Option<String>,
     /// The border around the view itself
+    #[serde(default)]
     child_border: Option<String>
 }
 
@@ -0,0 +1,172 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+use crate::sway::options::TogglableBool;
+
+/// Options for the `fullscreen` command. Either just the state (`fullscreen = "toggle"`), or a
+/// table that can also make the window fullscreen across all outputs
+/// (`fullscreen = { mode = "enable", global = true }`). An empty table toggles fullscreen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FullscreenParams {
+    Mode(TogglableBool),
+    Flags {
+        #[serde(default)]
+        mode: Option<TogglableBool>,
+        /// Fullscreen across all outputs
+        #[serde(default)]
+        global: bool,
+    },
+}
+
+impl FmtDisplay for FullscreenParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            FullscreenParams::Mode(m) => write!(f, "{m}"),
+            FullscreenParams::Flags { mode, global } => {
+                let mode = mode.clone().unwrap_or(TogglableBool::Toggle);
+                if *global { write!(f, "{mode} global") } else { write!(f, "{mode}") }
+            }
+        }
+    }
+}
+
+/// An opacity between 0 (transparent) and 1 (opaque).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "f64")]
+pub struct Opacity(f64);
+
+// opacities are always finite (see below), so they can be compared for equality
+impl Eq for Opacity {}
+
+impl TryFrom<f64> for Opacity {
+    type Error = String;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if (0.0..=1.0).contains(&value) { Ok(Opacity(value)) }
+        else { Err(format!("opacity must be between 0 and 1, got {value}")) }
+    }
+}
+
+impl FmtDisplay for Opacity {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How an opacity change is applied.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum OpacityChange {
+    #[default]
+    Set,
+    Plus,
+    Minus,
+}
+
+/// Options for the `opacity` command. Either just the opacity (`opacity = 0.9`), or a table that
+/// changes it relatively (`opacity = { change = "minus", value = 0.1 }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum OpacityParams {
+    Value(Opacity),
+    Change {
+        #[serde(default)]
+        change: OpacityChange,
+        value: Opacity,
+    },
+}
+
+impl FmtDisplay for OpacityParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            OpacityParams::Value(v) => write!(f, "{v}"),
+            OpacityParams::Change { change, value } => write!(f, "{change} {value}"),
+        }
+    }
+}
+
+/// Options for the `urgent` command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Urgent {
+    Enable,
+    Disable,
+    /// Allow the window to set itself as urgent (the default)
+    Allow,
+    /// Ignore urgency requests from the window
+    Deny,
+}
+
+/// Options for the `inhibit_idle` command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InhibitIdle {
+    /// Inhibit idle while the window is focused
+    Focus,
+    /// Inhibit idle while the window is fullscreen
+    Fullscreen,
+    /// Inhibit idle while the window is open
+    Open,
+    None,
+    /// Inhibit idle while the window is visible
+    Visible,
+}
+
+/// Placeholders that can be used in `title_format`.
+pub const TITLE_PLACEHOLDERS: [&str; 8] = [
+    "title", "app_id", "class", "instance", "shell", "sandbox_engine", "sandbox_app_id",
+    "sandbox_instance_id",
+];
+
+/// A window title format for the `title_format` command, such as `"%title (%app_id)"`. Unknown
+/// `%placeholders` are rejected, but a `%` that isn't followed by a name is kept as is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String")]
+pub struct TitleFormat(String);
+
+impl TryFrom<String> for TitleFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        for (i, _) in value.match_indices('%') {
+            let name: String = value[i + 1..].chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            // a `%` that isn't followed by an identifier is copied literally (like in "100%")
+            if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') { continue }
+            // like Sway, match placeholders by prefix (`%titlex` is `%title` followed by `x`)
+            if !TITLE_PLACEHOLDERS.iter().any(|p| name.starts_with(p)) {
+                return Err(format!("unknown title_format placeholder %{name} (expected one of %{})",
+                                   TITLE_PLACEHOLDERS.join(", %")))
+            }
+        }
+        Ok(TitleFormat(value))
+    }
+}
+
+impl FmtDisplay for TitleFormat {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
@@ -15,10 +15,11 @@
 //
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
 use crate::sway::options::{ArgMap};
 
-#[derive(PartialEq, Eq, Clone, Debug, Display, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case", untagged)]
 pub enum ExecParams {
     #[strum(serialize = "{0}")]
@@ -31,7 +32,7 @@ pub enum ExecParams {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema, Hash)]
 #[serde(rename_all = "kebab-case")]
 pub enum Exec {
     #[strum(serialize = "--no-startup-id")]
@@ -0,0 +1,69 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+
+/// Which mouse button resizes floating windows when dragging with the floating modifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ModifierMode {
+    /// Left button moves, right button resizes
+    Normal,
+    /// Right button moves, left button resizes
+    Inverse,
+}
+
+/// Options for the `floating_modifier` command. Either just the modifier
+/// (`floating-modifier = "$mod"`), or a table that also sets the mouse button mode
+/// (`floating-modifier = { modifier = "$mod", mode = "inverse" }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FloatingModifier {
+    Modifier(String),
+    WithMode {
+        modifier: String,
+        #[serde(default)]
+        mode: Option<ModifierMode>,
+    },
+}
+
+impl FmtDisplay for FloatingModifier {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            FloatingModifier::Modifier(m) | FloatingModifier::WithMode { modifier: m, mode: None } => write!(f, "{m}"),
+            FloatingModifier::WithMode { modifier, mode: Some(mode) } => write!(f, "{modifier} {mode}"),
+        }
+    }
+}
+
+/// A size limit for floating windows, in pixels. A value of -1 removes the limit, and 0 uses
+/// Sway's default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct FloatingSize {
+    width: i32,
+    height: i32,
+}
+
+impl FmtDisplay for FloatingSize {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} x {}", self.width, self.height)
+    }
+}
@@ -14,13 +14,15 @@
 // this program. If not, see <https://www.gnu.org/licenses/>.
 //
 
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
 use crate::sway::options;
 
 /// The base level focus parameter enum. This will differentiate into one of the ~9 different
 /// variants of the `focus` command in Sway when fully assembled.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum FocusParams {
@@ -39,8 +41,26 @@ pub enum FocusParams {
     ModeToggle
 }
 
+/// Options for the `focus` command. An empty table (`focus = {}`) focuses the window matched by
+/// the command's criteria.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Focus {
+    Params(FocusParams),
+    Matched {},
+}
+
+impl FmtDisplay for Focus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Focus::Params(p) => write!(f, "focus {p}"),
+            Focus::Matched {} => write!(f, "focus"),
+        }
+    }
+}
+
 /// Specific options for selecting focus outputs
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum FocusOutputOptions {
@@ -49,4 +69,61 @@ pub enum FocusOutputOptions {
     #[serde(untagged)]
     #[strum(serialize = "{0}")]
     Named(String)
-}
\ No newline at end of file
+}
+/// Options for the `focus_follows_mouse` command.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FocusFollowsMouse {
+    Yes,
+    No,
+    /// Focus windows under the mouse even when the mouse didn't leave the focused window
+    Always,
+}
+
+/// Options for the `focus_wrapping` command.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FocusWrapping {
+    Yes,
+    No,
+    /// Wrap even inside of tabbed / stacked containers
+    Force,
+    /// Only wrap between workspaces, not inside of them
+    Workspace,
+}
+
+/// Options for the `focus_on_window_activation` command.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FocusOnWindowActivation {
+    /// Focus the window if it is on a visible workspace, otherwise mark it urgent
+    Smart,
+    Urgent,
+    Focus,
+    None,
+}
+
+/// Options for the `mouse_warping` command.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MouseWarping {
+    Output,
+    Container,
+    None,
+}
+
+/// Options for the `popup_during_fullscreen` command.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PopupDuringFullscreen {
+    /// Show the popup if it belongs to the fullscreen window
+    Smart,
+    Ignore,
+    #[serde(alias = "leave_fullscreen")]
+    LeaveFullscreen,
+}
@@ -0,0 +1,138 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+use crate::sway::options::TogglableBool;
+
+/// Which gaps to change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GapType {
+    Inner,
+    Outer,
+    Horizontal,
+    Vertical,
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// Gap sizes in pixels (`gaps <type> <amount>`). Outer gaps can be negative, to cancel out inner
+/// gaps at the edges of the screen.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Gaps {
+    #[serde(default)]
+    inner: Option<i32>,
+    #[serde(default)]
+    outer: Option<i32>,
+    #[serde(default)]
+    horizontal: Option<i32>,
+    #[serde(default)]
+    vertical: Option<i32>,
+    #[serde(default)]
+    top: Option<i32>,
+    #[serde(default)]
+    right: Option<i32>,
+    #[serde(default)]
+    bottom: Option<i32>,
+    #[serde(default)]
+    left: Option<i32>,
+}
+
+impl Gaps {
+    /// `<type> <amount>` for every gap that is set.
+    pub fn args(&self) -> Vec<String> {
+        [
+            (GapType::Inner, self.inner),
+            (GapType::Outer, self.outer),
+            (GapType::Horizontal, self.horizontal),
+            (GapType::Vertical, self.vertical),
+            (GapType::Top, self.top),
+            (GapType::Right, self.right),
+            (GapType::Bottom, self.bottom),
+            (GapType::Left, self.left),
+        ].into_iter()
+            .filter_map(|(kind, amount)| amount.map(|a| format!("{kind} {a}")))
+            .collect()
+    }
+}
+
+/// Only show gaps on workspaces with more than one window (or, with `inverse_outer`, only show
+/// outer gaps on workspaces with a single window).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(untagged)]
+pub enum SmartGaps {
+    #[strum(to_string = "{0}")]
+    Toggle(TogglableBool),
+    #[strum(to_string = "{0}")]
+    InverseOuter(InverseOuter),
+}
+
+/// The `inverse_outer` setting of [SmartGaps].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InverseOuter {
+    #[serde(alias = "inverse_outer")]
+    InverseOuter,
+}
+
+/// Which workspaces a runtime gaps change applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GapScope {
+    All,
+    #[default]
+    Current,
+}
+
+/// How a runtime gaps change is applied.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GapChange {
+    #[default]
+    Set,
+    Plus,
+    Minus,
+    Toggle,
+}
+
+/// Runtime gaps command (`gaps <type> all|current set|plus|minus|toggle <amount>`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GapsParams {
+    #[serde(rename = "type")]
+    kind: GapType,
+    #[serde(default)]
+    scope: GapScope,
+    #[serde(default)]
+    change: GapChange,
+    amount: i32,
+}
+
+impl FmtDisplay for GapsParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {} {} {}", self.kind, self.scope, self.change, self.amount)
+    }
+}
@@ -15,11 +15,12 @@
 //
 
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
 use subenum::subenum;
 use crate::sway::options::ArgList;
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum LayoutParams {
@@ -29,7 +30,7 @@ pub enum LayoutParams {
     Cycle(LayoutCycleParams)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 #[strum(serialize_all = "kebab-case")]
 pub enum LayoutCycleParams {
@@ -40,7 +41,7 @@ pub enum LayoutCycleParams {
 }
 
 #[subenum(ConfigLayout)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum Layout {
@@ -59,7 +60,7 @@ pub enum Layout {
 }
 
 #[subenum(LayoutCycleSingle, LayoutCycleMulti)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum LayoutCycle {
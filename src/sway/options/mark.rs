@@ -0,0 +1,85 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+
+/// What happens to a window's existing marks when a new one is added.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MarkMode {
+    /// Keep the existing marks
+    #[strum(serialize = "--add")]
+    Add,
+    /// Replace the existing marks (Sway's default)
+    #[strum(serialize = "--replace")]
+    Replace,
+}
+
+/// Options for the `mark` command. Either just the mark (`mark = "a"`), or a table that also sets
+/// flags (`mark = { name = "a", mode = "add", toggle = true }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum MarkParams {
+    Name(String),
+    Flags {
+        name: String,
+        #[serde(default)]
+        mode: Option<MarkMode>,
+        /// Remove the mark instead if the window already has it
+        #[serde(default)]
+        toggle: bool,
+    },
+}
+
+impl MarkParams {
+    pub fn new(name: &str, mode: Option<MarkMode>, toggle: bool) -> Self {
+        MarkParams::Flags { name: name.to_string(), mode, toggle }
+    }
+}
+
+impl FmtDisplay for MarkParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            MarkParams::Name(name) => write!(f, "{name}"),
+            MarkParams::Flags { name, mode, toggle } => {
+                if let Some(m) = mode { write!(f, "{m} ")?; }
+                if *toggle { write!(f, "--toggle ")?; }
+                write!(f, "{name}")
+            }
+        }
+    }
+}
+
+/// Options for the `unmark` command: a single mark (`unmark = "a"`), or all marks (`unmark = {}`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum UnmarkParams {
+    Name(String),
+    All {},
+}
+
+impl FmtDisplay for UnmarkParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            UnmarkParams::Name(name) => write!(f, "unmark {name}"),
+            UnmarkParams::All {} => write!(f, "unmark"),
+        }
+    }
+}
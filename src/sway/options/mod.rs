@@ -28,12 +28,26 @@ pub mod mov;
 pub mod resize;
 /// All structs for client commands
 pub mod client;
+/// All structs for output commands
+pub mod output;
+/// All structs for gaps commands
+pub mod gaps;
+/// All structs for border, title bar and font commands
+pub mod appearance;
+/// All structs for floating window commands
+pub mod floating;
+/// All structs for mark commands
+pub mod mark;
+/// All structs for per-container commands (fullscreen, opacity, etc.)
+pub mod container;
 
 use subenum::subenum;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use std::hash::Hash;
 use serde::{Serialize, Deserialize};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::de::{Visitor, Error, Unexpected, Deserializer};
 use strum::Display;
 
@@ -58,7 +72,7 @@ pub enum TogglableBool {
 }
 
 /// Options used for the `split` command.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Split {
@@ -70,13 +84,17 @@ pub enum Split {
 }
 
 /// Different forms of workspace command options.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+///
+/// A plain number (`3`) is the workspace named "3". A table with a number and a name
+/// (`{ number = 3, name = "web" }`) is `number 3 web`: the workspace with number 3, whatever its
+/// name is, or a new workspace named "3 web" if there isn't one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case", untagged)]
 #[strum(serialize_all = "snake_case")]
 pub enum Workspace {
     #[strum(serialize = "{0}")]
     Numeric(u8),
-    #[strum(serialize = "{number} {name}")]
+    #[strum(serialize = "number {number} {name}")]
     Named{
         number: u8,
         #[serde(default)]
@@ -84,8 +102,53 @@ pub enum Workspace {
     },
 }
 
+/// Where to switch to with the `workspace` command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkspaceTarget {
+    #[serde(alias = "previous")]
+    Prev,
+    Next,
+    #[serde(alias = "previous-on-output")]
+    PrevOnOutput,
+    NextOnOutput,
+    BackAndForth,
+    /// The workspace whose name starts with this number (such as "3: web")
+    #[strum(serialize = "number {0}")]
+    Number(u8),
+    #[serde(untagged)]
+    #[strum(serialize = "{0}")]
+    Workspace(Workspace),
+}
+
+/// Options for the `workspace` command. Either just the target (`workspace = 3`,
+/// `workspace = "next"`), or a table that also sets flags
+/// (`workspace = { target = 3, no-auto-back-and-forth = true }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum WorkspaceParams {
+    Target(WorkspaceTarget),
+    Flags {
+        target: WorkspaceTarget,
+        /// Don't switch back to the previous workspace if this one is already focused, even if
+        /// `workspace-auto-back-and-forth` is enabled
+        #[serde(default, rename = "no-auto-back-and-forth")]
+        no_auto_back_and_forth: bool,
+    },
+}
+
+impl FmtDisplay for WorkspaceParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            WorkspaceParams::Target(t) | WorkspaceParams::Flags { target: t, no_auto_back_and_forth: false } => write!(f, "{t}"),
+            WorkspaceParams::Flags { target, no_auto_back_and_forth: true } => write!(f, "--no-auto-back-and-forth {target}"),
+        }
+    }
+}
+
 /// Options for parent/child hierarchy.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Hierarchy {
@@ -94,7 +157,7 @@ pub enum Hierarchy {
 }
 
 /// Options for sibling hierarchy.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Relative {
@@ -104,7 +167,7 @@ pub enum Relative {
 }
 
 /// Options for relative workspace commands
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum RelativeWorkspace {
@@ -115,7 +178,7 @@ pub enum RelativeWorkspace {
 }
 
 /// Options for directional arguments
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Directional {
@@ -126,22 +189,19 @@ pub enum Directional {
 }
 
 /// Positional units
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Units {
+    #[default]
     #[serde(alias = "pixels", alias = "pixel")]
     Px,
     #[serde(alias = "percent", alias = "%", alias = "points")]
     Ppt
 }
 
-impl Default for Units {
-    fn default() -> Self { Units::Px }
-}
-
 #[subenum(DefaultBorder)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Border {
@@ -158,7 +218,7 @@ pub enum Border {
     Toggle
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum DefaultOrientation {
@@ -169,7 +229,7 @@ pub enum DefaultOrientation {
     Auto
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Swap {
@@ -181,7 +241,7 @@ pub enum Swap {
     Mark(String)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case", untagged)]
 #[strum(serialize_all = "snake_case")]
 pub enum MaxRenderTimeOpts {
@@ -190,7 +250,7 @@ pub enum MaxRenderTimeOpts {
     Ms(u8)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case", untagged)]
 #[strum(serialize_all = "snake_case")]
 pub enum RenameOpts {
@@ -200,11 +260,40 @@ pub enum RenameOpts {
     Existing{ old: String, new: String },
 }
 
+/// A path to a file or directory.
+///
+/// A leading `~` is expanded to the user's home directory when the config is parsed, since Sway
+/// only does this for some commands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct FilePath(String);
+
+impl FmtDisplay for FilePath {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<&str> for FilePath {
+    fn from(path: &str) -> Self {
+        Self(crate::environment::expand_tilde(path))
+    }
+}
+
+impl<'de> Deserialize<'de> for FilePath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        String::deserialize(deserializer).map(|s| FilePath::from(s.as_str()))
+    }
+}
+
 /// An array of values.
 ///
 /// A [Vec] would normally suffice for our purposes, but this struct implements [Display],
 /// [Default], and [Serialize]/[Deserialize] traits to be compatible with everything else.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct ArgList<T: FmtDisplay>(Vec<T>);
 
@@ -224,6 +313,7 @@ impl<T: FmtDisplay> Default for ArgList<T> {
     }
 }
 
+#[allow(dead_code)]
 impl<T: FmtDisplay> ArgList<T> {
     pub fn new() -> Self {
         Self::default()
@@ -238,16 +328,16 @@ impl<T: FmtDisplay> ArgList<T> {
 ///
 /// A [Vec] would normally suffice for our purposes, but this struct implements [Display],
 /// [Default], and [Serialize]/[Deserialize] traits to be compatible with everything else.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct ArgMap<T: FmtDisplay + Eq + Hash>(HashMap<T, bool>);
 
 impl<T: FmtDisplay + Eq + Hash> FmtDisplay for ArgMap<T> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "{}", self.0.keys()
-            .map(|a| if *self.0.get(a).unwrap_or(&false) { format!("{a} ") } else { String::new() })
-            .collect::<Vec<String>>()
-            .join(""))
+        // sorted, so that the output doesn't change between runs
+        let mut args = self.enabled().map(|a| format!("{a} ")).collect::<Vec<String>>();
+        args.sort();
+        write!(f, "{}", args.join(""))
     }
 }
 
@@ -257,6 +347,7 @@ impl<T: FmtDisplay + Eq + Hash> Default for ArgMap<T> {
     }
 }
 
+#[allow(dead_code)]
 impl<T: FmtDisplay + Eq + Hash> ArgMap<T> {
     pub fn new() -> Self {
         Self::default()
@@ -269,6 +360,11 @@ impl<T: FmtDisplay + Eq + Hash> ArgMap<T> {
     pub fn insert(&mut self, key: T, value: bool) -> Option<bool> {
         self.0.insert(key, value)
     }
+
+    /// All arguments that are enabled.
+    pub fn enabled(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().filter(|(_, v)| **v).map(|(k, _)| k)
+    }
 }
 
 // since serde doesn't offer an easy way to support deserializing multiple types into a single enum,
@@ -308,4 +404,21 @@ impl<'de> Deserialize<'de> for TogglableBool {
     {
         deserializer.deserialize_any::<TogglableBool>(TogglableBool::Disable)
     }
+}
+
+// the schema has to match the custom `Deserialize` implementation above
+impl JsonSchema for TogglableBool {
+    fn schema_name() -> Cow<'static, str> {
+        "TogglableBool".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Enable, disable or toggle a setting",
+            "anyOf": [
+                { "type": "boolean" },
+                { "enum": ["true", "yes", "enable", "false", "no", "disable", "toggle"] }
+            ]
+        })
+    }
 }
\ No newline at end of file
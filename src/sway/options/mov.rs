@@ -14,32 +14,65 @@
 // this program. If not, see <https://www.gnu.org/licenses/>.
 //
 
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
 use crate::sway::options;
 
 /// The base level move parameter enum. This will differentiate into one of the ~14 different
 /// variants of the `move` command in Sway when fully assembled.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum MoveParams {
     #[strum(serialize = "{0}")]
-    Directional(options::Directional),
+    Directional(DirectionalParams),
     #[strum(serialize = "position {0}")]
     Position(PositionParams),
     #[serde(alias = "absolute")]
     #[strum(serialize = "absolute position {0}")]
     AbsolutePosition(AbsolutePositionParams),
-    #[strum(serialize = "container to {0}")]
-    Container(MoveContainerParams),
+    /// Move the focused container (`window` is the same as `container`)
+    #[serde(alias = "window")]
+    #[strum(serialize = "{0}")]
+    Container(MoveContainer),
+    /// Move the focused workspace to another output
     #[strum(serialize = "workspace to output {0}")]
-    Workspace(MoveToOutputParams),
+    Workspace(MoveWorkspaceToOutputParams),
+    /// Move the focused container to a mark (defined using mark command)
+    #[strum(serialize = "to mark {0}")]
+    Mark(String),
+    /// Move the focused container to the scratchpad
+    #[serde(alias = "to-scratchpad")]
+    Scratchpad,
+}
+
+/// Move in a direction, optionally by a certain distance (for floating containers).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum DirectionalParams {
+    Direction(options::Directional),
+    Distance {
+        direction: options::Directional,
+        amount: u32,
+        #[serde(default)]
+        unit: options::Units,
+    },
+}
+
+impl FmtDisplay for DirectionalParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            DirectionalParams::Direction(d) => write!(f, "{d}"),
+            DirectionalParams::Distance { direction, amount, unit } => write!(f, "{direction} {amount} {unit}"),
+        }
+    }
 }
 
 /// Move to position variants.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case", untagged)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum PositionParams {
     /// Move to the center of the current output
@@ -51,15 +84,15 @@ pub enum PositionParams {
     #[serde(untagged)]
     #[strum(serialize = "{x} {unit} {y} {unit}")]
     Coordinates{
-        x: u8,
-        y: u8,
+        x: i32,
+        y: i32,
         #[serde(default)]
         unit: options::Units
     }
 }
 
 /// Move to absolute position variants. This is based on all monitors.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum AbsolutePositionParams {
@@ -68,11 +101,36 @@ pub enum AbsolutePositionParams {
     /// Move to the specified position relative to all outputs
     #[serde(untagged)]
     #[strum(serialize = "{x} px {y} px")]
-    Coordinates{ x: u8, y: u8 },
+    Coordinates{ x: i32, y: i32 },
+}
+
+/// Options for moving the focused container. Either just the destination
+/// (`move.container = 3`), or a table that also sets flags
+/// (`move.container = { to = 3, no-auto-back-and-forth = true }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum MoveContainer {
+    To(MoveContainerParams),
+    Flags {
+        to: MoveContainerParams,
+        /// Don't move to the previous workspace if the destination is the current workspace, even
+        /// if `workspace-auto-back-and-forth` is enabled
+        #[serde(default, rename = "no-auto-back-and-forth")]
+        no_auto_back_and_forth: bool,
+    },
+}
+
+impl FmtDisplay for MoveContainer {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            MoveContainer::To(to) | MoveContainer::Flags { to, no_auto_back_and_forth: false } => write!(f, "container to {to}"),
+            MoveContainer::Flags { to, no_auto_back_and_forth: true } => write!(f, "--no-auto-back-and-forth container to {to}"),
+        }
+    }
 }
 
 /// Move container variants.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum MoveContainerParams {
@@ -91,7 +149,7 @@ pub enum MoveContainerParams {
 }
 
 /// Move container to workspace variants.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum MoveContainerToWorkspaceParams {
@@ -103,14 +161,35 @@ pub enum MoveContainerToWorkspaceParams {
     OnOutput(options::Relative),
     /// Back to previously focused workspace
     BackAndForth,
+    /// The workspace whose name starts with this number (such as "3: web")
+    #[strum(serialize = "number {0}")]
+    Number(u8),
     /// To numbered / named workspace
     #[serde(untagged)]
     #[strum(to_string = "{0}")]
     Workspace(options::Workspace)
 }
 
+/// Move workspace to output params. With a list of outputs, the workspace is moved to the first
+/// one that exists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum MoveWorkspaceToOutputParams {
+    Output(MoveToOutputParams),
+    Outputs(Vec<String>),
+}
+
+impl FmtDisplay for MoveWorkspaceToOutputParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            MoveWorkspaceToOutputParams::Output(o) => write!(f, "{o}"),
+            MoveWorkspaceToOutputParams::Outputs(o) => write!(f, "{}", o.join(" ")),
+        }
+    }
+}
+
 /// Move container/workspace to output params.
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum MoveToOutputParams {
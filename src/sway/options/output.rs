@@ -0,0 +1,152 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use strum::Display;
+use crate::sway::options::{FilePath, TogglableBool};
+
+/// Settings for a single output (see sway-output(5)).
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct OutputParams {
+    /// Enable, disable or toggle the output
+    #[serde(default)]
+    enable: Option<TogglableBool>,
+    /// Resolution and (optionally) refresh rate, such as `1920x1080@60Hz`
+    #[serde(default, alias = "resolution", alias = "res")]
+    mode: Option<String>,
+    /// Position of the output in the layout
+    #[serde(default, alias = "pos")]
+    position: Option<Position>,
+    /// Scale factor
+    #[serde(default)]
+    scale: Option<Scale>,
+    /// Rotation / flipping
+    #[serde(default)]
+    transform: Option<Transform>,
+    /// Adaptive sync (variable refresh rate)
+    #[serde(default)]
+    adaptive_sync: Option<bool>,
+    /// Background image
+    #[serde(default, alias = "background")]
+    bg: Option<Background>,
+}
+
+impl FmtDisplay for OutputParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut args = Vec::new();
+        if let Some(e) = &self.enable { args.push(e.to_string()) }
+        if let Some(m) = &self.mode { args.push(format!("mode {m}")) }
+        if let Some(p) = &self.position { args.push(format!("position {p}")) }
+        if let Some(s) = &self.scale { args.push(format!("scale {s}")) }
+        if let Some(t) = &self.transform { args.push(format!("transform {t}")) }
+        if let Some(a) = &self.adaptive_sync { args.push(format!("adaptive_sync {}", if *a { "on" } else { "off" })) }
+        if let Some(b) = &self.bg { args.push(format!("bg {b}")) }
+        write!(f, "{}", args.join(" "))
+    }
+}
+
+/// Output position, in layout coordinates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl FmtDisplay for Position {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+/// Output scale factor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "f64")]
+pub struct Scale(f64);
+
+impl TryFrom<f64> for Scale {
+    type Error = String;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if value.is_finite() && value > 0.0 { Ok(Scale(value)) }
+        else { Err(format!("scale must be a positive number, got {value}")) }
+    }
+}
+
+impl FmtDisplay for Scale {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+// scale factors are always finite (see above), so they can be compared for equality
+impl Eq for Scale {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Transform {
+    Normal,
+    #[serde(rename = "90")]
+    #[strum(to_string = "90")]
+    Rotate90,
+    #[serde(rename = "180")]
+    #[strum(to_string = "180")]
+    Rotate180,
+    #[serde(rename = "270")]
+    #[strum(to_string = "270")]
+    Rotate270,
+    Flipped,
+    #[serde(rename = "flipped-90")]
+    #[strum(to_string = "flipped-90")]
+    Flipped90,
+    #[serde(rename = "flipped-180")]
+    #[strum(to_string = "flipped-180")]
+    Flipped180,
+    #[serde(rename = "flipped-270")]
+    #[strum(to_string = "flipped-270")]
+    Flipped270,
+}
+
+/// Background image or color for an output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case", untagged)]
+pub enum Background {
+    #[strum(to_string = "{file} {mode}")]
+    Image {
+        file: FilePath,
+        #[serde(default)]
+        mode: BackgroundMode,
+    },
+    #[strum(to_string = "{color} solid_color")]
+    Color {
+        color: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BackgroundMode {
+    Stretch,
+    #[default]
+    Fill,
+    Fit,
+    Center,
+    Tile,
+}
@@ -14,11 +14,13 @@
 // this program. If not, see <https://www.gnu.org/licenses/>.
 //
 
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use strum::Display;
-use crate::sway::options::Units;
+use crate::sway::options::{Directional, Units};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
 pub enum ResizeParams {
@@ -31,51 +33,119 @@ pub enum ResizeParams {
     Set (ResizeSetParams)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
-#[serde(rename_all = "kebab-case", untagged)]
-#[strum(serialize_all = "snake_case")]
+/// The other unit, used for the `or` fallback of a resize amount.
+fn fallback_unit(unit: &Units) -> Units {
+    match unit {
+        Units::Px => Units::Ppt,
+        Units::Ppt => Units::Px,
+    }
+}
+
+/// Write a resize amount, such as `10 px` or `10 px or 5 ppt`.
+fn write_amount(f: &mut Formatter, amount: u32, unit: &Units, or: Option<u32>) -> FmtResult {
+    match or {
+        Some(o) => write!(f, "{amount} {unit} or {o} {}", fallback_unit(unit)),
+        None => write!(f, "{amount} {unit}"),
+    }
+}
+
+/// Options for growing or shrinking a container.
+///
+/// `or` is an amount in the other unit (ppt if `unit` is px, and vice versa). Sway uses the px
+/// amount for floating containers and the ppt amount for tiled ones.
+// unknown fields are denied, since untagged variants would otherwise silently ignore keys meant
+// for another variant (such as `height` next to `width`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case", untagged, deny_unknown_fields)]
 pub enum SingleAxisParams {
-    #[strum(serialize = "width {width} {unit}")]
     Width{
         #[serde(alias = "x")]
-        width: u8,
+        width: u32,
         #[serde(default)]
-        unit: Units
+        unit: Units,
+        #[serde(default)]
+        or: Option<u32>,
     },
-    #[strum(serialize = "height {height} {unit}")]
     Height{
         #[serde(alias = "y")]
-        height: u8,
+        height: u32,
         #[serde(default)]
-        unit: Units
+        unit: Units,
+        #[serde(default)]
+        or: Option<u32>,
+    },
+    /// Grow or shrink the edge in a certain direction
+    Directional{
+        direction: Directional,
+        amount: u32,
+        #[serde(default)]
+        unit: Units,
+        #[serde(default)]
+        or: Option<u32>,
+    },
+}
+
+impl FmtDisplay for SingleAxisParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            SingleAxisParams::Width { width, unit, or } => {
+                write!(f, "width ")?;
+                write_amount(f, *width, unit, *or)
+            }
+            SingleAxisParams::Height { height, unit, or } => {
+                write!(f, "height ")?;
+                write_amount(f, *height, unit, *or)
+            }
+            SingleAxisParams::Directional { direction, amount, unit, or } => {
+                write!(f, "{direction} ")?;
+                write_amount(f, *amount, unit, *or)
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
-#[serde(rename_all = "kebab-case", untagged)]
-#[strum(serialize_all = "snake_case")]
+/// Options for setting the size of a container. When setting both axes, `width-unit` and
+/// `height-unit` override `unit` for their axis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case", untagged, deny_unknown_fields)]
 pub enum ResizeSetParams{
-    #[strum(serialize = "width {width} {unit}")]
-    Width{
+    // this has to come first, since the single axis variants would ignore the other axis
+    Both{
         #[serde(alias = "x")]
-        width: u8,
+        width: u32,
+        #[serde(alias = "y")]
+        height: u32,
         #[serde(default)]
-        unit: Units
+        unit: Units,
+        #[serde(default)]
+        width_unit: Option<Units>,
+        #[serde(default)]
+        height_unit: Option<Units>,
     },
-    #[strum(serialize = "height {height} {unit}")]
-    Height{
-        #[serde(alias = "y")]
-        height: u8,
+    Width{
+        #[serde(alias = "x")]
+        width: u32,
         #[serde(default)]
         unit: Units
     },
-    #[strum(serialize = "width {width} {unit} height {height} {unit}")]
-    Both{
-        #[serde(alias = "x")]
-        width: u8,
+    Height{
         #[serde(alias = "y")]
-        height: u8,
+        height: u32,
         #[serde(default)]
         unit: Units
     },
-}
\ No newline at end of file
+}
+
+impl FmtDisplay for ResizeSetParams {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            ResizeSetParams::Both { width, height, unit, width_unit, height_unit } => {
+                let width_unit = width_unit.as_ref().unwrap_or(unit);
+                let height_unit = height_unit.as_ref().unwrap_or(unit);
+                write!(f, "width {width} {width_unit} height {height} {height_unit}")
+            }
+            ResizeSetParams::Width { width, unit } => write!(f, "width {width} {unit}"),
+            ResizeSetParams::Height { height, unit } => write!(f, "height {height} {unit}"),
+        }
+    }
+}
@@ -0,0 +1,559 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use crate::sway::analysis::NormalizedKeys;
+use crate::sway::config::BindKind;
+use crate::sway::vars::Variables;
+
+/// What a statement configures. Two statements with the same key configure the same thing, so
+/// their values can be compared.
+#[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord)]
+pub enum Key {
+    /// A variable (`set $name`)
+    Set(String),
+    /// A key binding in a mode
+    Binding { mode: String, kind: BindKind, keys: NormalizedKeys },
+    /// A setting that can only have one value, such as `focus_follows_mouse` or
+    /// `output eDP-1 scale`. The last statement for a setting wins, like in Sway.
+    Setting { mode: String, name: String },
+    /// Any other statement (such as `exec` or `for_window`, which can be repeated). These have no
+    /// value, and are only compared by whether they exist.
+    Other { mode: String, text: String },
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let (text, mode) = match self {
+            Key::Set(name) => (format!("set ${name}"), "default"),
+            Key::Binding { mode, kind, keys } => (format!("{kind} {keys}"), mode.as_str()),
+            Key::Setting { mode, name } => (name.clone(), mode.as_str()),
+            Key::Other { mode, text } => (text.clone(), mode.as_str()),
+        };
+        if mode == "default" { write!(f, "{text}") } else { write!(f, "{text} (in mode {mode})") }
+    }
+}
+
+/// The statements of a Sway config file, keyed by what they configure.
+///
+/// Comments and formatting are dropped, `mode` / `bar` / `output` / etc. blocks are flattened,
+/// variables are substituted (except in `set` statements themselves), and key combinations are
+/// normalized (see [NormalizedKeys]). Commands are parsed into a canonical form, so equivalent
+/// spellings of the same command (`fullscreen` and `fullscreen toggle`, `move window` and
+/// `move container`, `yes` and `enable`, quoted and unquoted arguments, ...) compare as equal.
+/// This makes handwritten configs comparable with generated ones.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Statements(BTreeMap<Key, String>);
+
+/// Split a config into logical lines (joining continuation lines), paired with the mode they are in.
+/// Block headers other than `mode` are prepended to the lines inside them.
+fn lines(text: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut blocks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(l) = line.strip_suffix('\\') {
+            current.push_str(l);
+            current.push(' ');
+            continue
+        }
+        current.push_str(line);
+        let line = std::mem::take(&mut current);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue }
+
+        if line == "}" {
+            blocks.pop();
+        } else if let Some(header) = line.strip_suffix('{') {
+            blocks.push(header.trim().to_string());
+        } else {
+            let mut mode = "default".to_string();
+            let mut prefix = Vec::new();
+            for block in &blocks {
+                match block.strip_prefix("mode ") {
+                    // modes can have flags like --pango_markup before their name
+                    Some(name) => mode = unquote(name.split_whitespace().last().unwrap_or_default()),
+                    None => prefix.push(block.as_str()),
+                }
+            }
+            prefix.push(line);
+            result.push((mode, prefix.join(" ")));
+        }
+    }
+    result
+}
+
+fn unquote(s: &str) -> String {
+    s.trim_matches('"').to_string()
+}
+
+/// Split a statement into words. Quotes group words together (and are dropped), criteria
+/// (`[...]`) are kept as a single word, and the command separators `;` and `,` are words of their
+/// own.
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    // whether the current word was quoted, so that `""` is still a word
+    let mut quoted = false;
+    let mut quote: Option<char> = None;
+    let mut depth = 0;
+    fn flush(words: &mut Vec<String>, current: &mut String, quoted: &mut bool) {
+        if !current.is_empty() || *quoted { words.push(std::mem::take(current)) }
+        *quoted = false;
+    }
+    for c in s.chars() {
+        match quote {
+            Some(q) if c == q => {
+                quote = None;
+                if depth > 0 { current.push(c) }
+            }
+            Some(_) => current.push(c),
+            None => match c {
+                '"' | '\'' => {
+                    quote = Some(c);
+                    quoted = true;
+                    if depth > 0 { current.push(c) }
+                }
+                '[' if current.is_empty() || depth > 0 => {
+                    depth += 1;
+                    current.push(c);
+                }
+                ']' if depth > 0 => {
+                    depth -= 1;
+                    current.push(c);
+                }
+                c if depth > 0 => current.push(c),
+                ';' | ',' => {
+                    flush(&mut words, &mut current, &mut quoted);
+                    words.push(c.to_string());
+                }
+                c if c.is_whitespace() => flush(&mut words, &mut current, &mut quoted),
+                c => current.push(c),
+            },
+        }
+    }
+    flush(&mut words, &mut current, &mut quoted);
+    words
+}
+
+/// Join words for display, quoting the ones that contain whitespace.
+fn join(words: &[String]) -> String {
+    words.iter()
+        .map(|w| if w.contains(char::is_whitespace) { format!("\"{w}\"") } else { w.clone() })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Spellings of booleans that Sway accepts, and the canonical spelling they are replaced with.
+const BOOLEANS: [(&str, &[&str]); 2] = [
+    ("enable", &["1", "yes", "on", "true", "enable", "enabled", "active"]),
+    ("disable", &["0", "no", "off", "false", "disable", "disabled", "inactive"]),
+];
+
+/// Commands and config directives whose first argument is a boolean.
+const BOOLEAN_COMMANDS: [&str; 13] = [
+    "fullscreen", "sticky", "floating", "urgent", "shortcuts_inhibitor", "focus_follows_mouse",
+    "workspace_auto_back_and_forth", "smart_borders", "smart_gaps", "show_marks", "xwayland",
+    "tiling_drag", "focus_wrapping",
+];
+
+/// Config directives that can be repeated, so they aren't settings with a single value.
+const REPEATABLE: [&str; 11] = [
+    "exec", "exec_always", "for_window", "assign", "no_focus", "include", "bindswitch",
+    "bindgesture", "unbindsym", "unbindcode", "bar",
+];
+
+/// Output subcommands, with their canonical name and how many arguments they take (`None` for
+/// the rest of the line).
+const OUTPUT_COMMANDS: [(&str, &str, Option<usize>); 20] = [
+    ("mode", "mode", Some(1)), ("resolution", "mode", Some(1)), ("res", "mode", Some(1)),
+    ("position", "position", Some(2)), ("pos", "position", Some(2)), ("scale", "scale", Some(1)),
+    ("scale_filter", "scale_filter", Some(1)), ("subpixel", "subpixel", Some(1)),
+    ("transform", "transform", Some(1)), ("adaptive_sync", "adaptive_sync", Some(1)),
+    ("render_bit_depth", "render_bit_depth", Some(1)), ("max_render_time", "max_render_time", Some(1)),
+    ("allow_tearing", "allow_tearing", Some(1)), ("power", "power", Some(1)), ("dpms", "power", Some(1)),
+    ("enable", "enable", Some(0)), ("disable", "enable", Some(0)), ("toggle", "enable", Some(0)),
+    ("bg", "bg", Some(2)), ("background", "bg", Some(2)),
+];
+
+fn boolean(word: &str) -> String {
+    let lower = word.to_lowercase();
+    BOOLEANS.iter()
+        .find(|(_, spellings)| spellings.contains(&lower.as_str()))
+        .map(|(canonical, _)| canonical.to_string())
+        .unwrap_or_else(|| word.to_string())
+}
+
+fn is_number(word: &str) -> bool {
+    word.parse::<f64>().is_ok()
+}
+
+/// Criteria with lowercase attribute names, quoted values and a stable order.
+fn criteria(word: &str) -> String {
+    let inner = word.strip_prefix('[').and_then(|w| w.strip_suffix(']')).unwrap_or(word);
+    let mut attrs: Vec<String> = words(inner).iter()
+        .map(|a| match a.split_once('=') {
+            Some((k, v)) => format!("{}=\"{}\"", k.trim().to_lowercase(), v.trim()),
+            None => a.to_lowercase(),
+        })
+        .collect();
+    attrs.sort();
+    format!("[{}]", attrs.join(" "))
+}
+
+/// A list of commands separated by `;` or `,`, each in canonical form (see [command]).
+fn command_list(words: &[String]) -> String {
+    let mut result = String::new();
+    let mut current: Vec<String> = Vec::new();
+    for word in words {
+        if word == ";" || word == "," {
+            result.push_str(&format!("{}{word} ", command(&current).join(" ")));
+            current.clear();
+        } else {
+            current.push(word.clone());
+        }
+    }
+    result.push_str(&command(&current).join(" "));
+    result.trim_end().to_string()
+}
+
+/// A single command (or config directive) in canonical form: arguments that Sway treats the same
+/// way are replaced with a single spelling, and optional words that have a default are filled in.
+fn command(words: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = words.iter().take_while(|w| w.starts_with('[')).map(|w| criteria(w)).collect();
+    let Some((name, args)) = words[result.len()..].split_first() else { return result };
+    let mut name = name.to_lowercase();
+    let mut args = args.to_vec();
+    match name.as_str() {
+        "exec" | "exec_always" => (),
+        "for_window" if !args.is_empty() => {
+            let rest = command_list(&args[1..]);
+            args = vec![criteria(&args[0]), rest];
+        }
+        "splitv" | "splith" | "splitt" => {
+            args = vec![name[5..].to_string()];
+            name = "split".to_string();
+        }
+        "move" => args = move_args(args),
+        "mark" => {
+            if !args.iter().any(|a| a == "--add" || a == "--replace") { args.insert(0, "--replace".to_string()) }
+            let (mut flags, rest): (Vec<String>, Vec<String>) = args.into_iter().partition(|a| a.starts_with("--"));
+            flags.sort();
+            args = flags.into_iter().chain(rest).collect();
+        }
+        "opacity" if args.first().is_some_and(|a| is_number(a)) => args.insert(0, "set".to_string()),
+        "floating_modifier" if args.len() == 1 && args[0] != "none" => args.push("normal".to_string()),
+        "fullscreen" if args.is_empty() => args.push("toggle".to_string()),
+        _ => (),
+    }
+    if name == "split" {
+        if let Some(a) = args.first_mut() {
+            *a = match a.as_str() { "v" => "vertical", "h" => "horizontal", "t" => "toggle", a => a }.to_string();
+        }
+    }
+    if BOOLEAN_COMMANDS.contains(&name.as_str()) {
+        if let Some(a) = args.first_mut() { *a = boolean(a) }
+    }
+    result.push(name);
+    result.extend(args);
+    result
+}
+
+/// Arguments of the `move` command in canonical form.
+fn move_args(args: Vec<String>) -> Vec<String> {
+    let (mut flags, mut rest): (Vec<String>, Vec<String>) = args.into_iter().partition(|a| a.starts_with("--"));
+    flags.sort();
+    let lower: Vec<String> = rest.iter().map(|w| w.to_lowercase()).collect();
+    let word = |i: usize| lower.get(i).map(|w| w.as_str());
+    let container = |rest: &mut Vec<String>, skip: usize| {
+        rest.drain(..skip);
+        rest.splice(0..0, ["container".to_string(), "to".to_string()]);
+    };
+    match word(0) {
+        // `move workspace [to] [output] <name>`
+        Some("workspace") if matches!(word(1), Some("to" | "output")) => {
+            let mut skip = 1;
+            if word(skip) == Some("to") { skip += 1 }
+            if word(skip) == Some("output") { skip += 1 }
+            rest.drain(..skip);
+            rest.splice(0..0, ["workspace".to_string(), "to".to_string(), "output".to_string()]);
+        }
+        // `move [container|window] [to] workspace|output|mark|scratchpad ...`
+        Some("container" | "window") => container(&mut rest, 1 + usize::from(word(1) == Some("to"))),
+        Some("to") => container(&mut rest, 1),
+        Some("workspace" | "output" | "mark" | "scratchpad") => container(&mut rest, 0),
+        // `move <direction> [<amount> [px|ppt]]`
+        Some("left" | "right" | "up" | "down") if rest.len() == 2 && is_number(&rest[1]) => rest.push("px".to_string()),
+        Some("position" | "absolute") => {
+            let mut units = Vec::new();
+            for (i, w) in rest.iter().enumerate() {
+                let w = match w.as_str() { "cursor" | "pointer" => "mouse".to_string(), w => w.to_string() };
+                let needs_unit = is_number(&w) && !matches!(lower.get(i + 1).map(|n| n.as_str()), Some("px" | "ppt"));
+                units.push(w);
+                if needs_unit { units.push("px".to_string()) }
+            }
+            rest = units;
+        }
+        _ => (),
+    }
+    flags.extend(rest);
+    flags
+}
+
+/// Split a statement (in canonical form) into settings, as `(name, value)` pairs. Statements that
+/// aren't settings (because they can be repeated) return nothing.
+fn settings(words: &[String]) -> Vec<(Vec<String>, Vec<String>)> {
+    let Some(name) = words.first() else { return Vec::new() };
+    let split = |n: usize| if words.len() > n { vec![(words[..n].to_vec(), words[n..].to_vec())] } else { Vec::new() };
+    match name.as_str() {
+        n if REPEATABLE.contains(&n) => Vec::new(),
+        "output" if words.len() > 2 => output_settings(&words[..2], &words[2..]),
+        "input" | "seat" => split(3).into_iter()
+            .map(|(k, mut v)| { v[0] = boolean(&v[0]); (k, v) })
+            .collect(),
+        "gaps" => split(2),
+        "workspace" => match words.get(2).map(|w| w.as_str()) {
+            Some("output") => split(3),
+            Some("gaps") => split(4),
+            _ => Vec::new(),
+        },
+        _ => split(1),
+    }
+}
+
+/// The settings in the arguments of an `output` statement, which can set several things at once
+/// (`output eDP-1 mode 1920x1080 scale 2`).
+fn output_settings(output: &[String], mut args: &[String]) -> Vec<(Vec<String>, Vec<String>)> {
+    let mut settings = Vec::new();
+    while let Some((sub, rest)) = args.split_first() {
+        let lower = sub.to_lowercase();
+        let (name, count) = match OUTPUT_COMMANDS.iter().find(|(n, _, _)| *n == lower) {
+            Some((_, name, Some(count))) => (name.to_string(), *count),
+            _ => (lower.clone(), rest.len()),
+        };
+        // optional extra arguments
+        let extra = match name.as_str() {
+            "mode" => usize::from(rest.first().is_some_and(|a| a.starts_with("--"))),
+            "transform" => usize::from(matches!(rest.get(1).map(|a| a.as_str()), Some("clockwise" | "anticlockwise"))),
+            "bg" => usize::from(rest.get(2).is_some_and(|a| a.starts_with('#'))),
+            _ => 0,
+        };
+        let count = (count + extra).min(rest.len());
+        let mut value: Vec<String> = match name.as_str() {
+            "enable" => vec![lower],
+            _ => rest[..count].to_vec(),
+        };
+        if matches!(name.as_str(), "adaptive_sync" | "power" | "allow_tearing") {
+            if let Some(v) = value.first_mut() { *v = boolean(v) }
+        }
+        let mut key = output.to_vec();
+        key.push(name);
+        if value.is_empty() { value.push(String::new()) }
+        settings.push((key, value));
+        args = &rest[count..];
+    }
+    settings
+}
+
+impl Statements {
+    /// Parse the text of a Sway config file. This never fails; anything that isn't recognized is
+    /// kept as an [Key::Other] statement.
+    pub fn parse(text: &str) -> Self {
+        let lines = lines(text);
+
+        let mut set = HashMap::new();
+        for (_, line) in &lines {
+            let words = words(line);
+            if let [first, name, value @ ..] = words.as_slice() {
+                if first == "set" { set.insert(name.trim_start_matches('$').to_string(), value.join(" ")); }
+            }
+        }
+        let vars = Variables::new(&set).unwrap_or_else(|e| {
+            log::warn!("{}", e);
+            Variables::default()
+        });
+
+        let mut statements = BTreeMap::new();
+        for (mode, line) in lines {
+            let raw = words(&line);
+            let kind = match raw.first().map(|w| w.as_str()) {
+                Some("set") if raw.len() > 1 => {
+                    let name = raw[1].trim_start_matches('$').to_string();
+                    statements.insert(Key::Set(name), raw[2..].join(" "));
+                    continue
+                }
+                Some("bindsym") => Some(BindKind::Sym),
+                Some("bindcode") => Some(BindKind::Code),
+                _ => None,
+            };
+            let words = words(&vars.substitute(&line));
+            if let Some(kind) = kind {
+                let mut flags: Vec<&String> = words[1..].iter().take_while(|w| w.starts_with("--")).collect();
+                if let Some(keys) = words.get(flags.len() + 1) {
+                    let command = command_list(&words[flags.len() + 2..]);
+                    let key = Key::Binding { mode: mode.clone(), kind, keys: NormalizedKeys::new(keys, &vars) };
+                    flags.sort();
+                    let value = flags.iter().map(|f| f.as_str()).chain([command.as_str()]).collect::<Vec<&str>>().join(" ");
+                    statements.insert(key, value);
+                    continue
+                }
+            }
+            let canonical = command(&words);
+            let settings = settings(&canonical);
+            if settings.is_empty() {
+                statements.insert(Key::Other { mode: mode.clone(), text: join(&canonical) }, String::new());
+            }
+            for (name, value) in settings {
+                // later statements override earlier ones
+                statements.insert(Key::Setting { mode: mode.clone(), name: join(&name) }, value.join(" "));
+            }
+        }
+        Statements(statements)
+    }
+}
+
+/// A difference between a generated config and the config Sway is actually running.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Difference {
+    /// The statement is in both configs, but with different values
+    Changed { key: Key, generated: String, live: String },
+    /// The statement is only in the generated config
+    Removed { key: Key, generated: String },
+    /// The statement is only in the live config
+    Added { key: Key, live: String },
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let join = |key: &Key, value: &str| if value.is_empty() { key.to_string() } else { format!("{key}: {value}") };
+        match self {
+            Difference::Changed { key, generated, live } =>
+                write!(f, "~ {key}\n    generated: {generated}\n    live:      {live}"),
+            Difference::Removed { key, generated } => write!(f, "- {}", join(key, generated)),
+            Difference::Added { key, live } => write!(f, "+ {}", join(key, live)),
+        }
+    }
+}
+
+/// Compare a generated config with a live one.
+pub fn compare(generated: &Statements, live: &Statements) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    for (key, g) in &generated.0 {
+        match live.0.get(key) {
+            Some(l) if l == g => (),
+            Some(l) => differences.push(Difference::Changed { key: key.clone(), generated: g.clone(), live: l.clone() }),
+            None => differences.push(Difference::Removed { key: key.clone(), generated: g.clone() }),
+        }
+    }
+    for (key, l) in &live.0 {
+        if !generated.0.contains_key(key) {
+            differences.push(Difference::Added { key: key.clone(), live: l.clone() });
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let stmts = Statements::parse("# comment\
+            \nset $mod Mod4\
+            \nset $term foot\
+            \nbindsym $mod+Return exec \\\
+            \n    $term\
+            \nbindsym --no-repeat --locked super+shift+q kill\
+            \nmode \"resize\" {\
+            \n    bindsym Escape mode default\
+            \n}\
+            \noutput eDP-1 {\
+            \n    scale 2\
+            \n}\n");
+        let keys = |k: &str| NormalizedKeys::new(k, &Variables::default());
+        let binding = |mode: &str, k: &str| Key::Binding { mode: mode.to_string(), kind: BindKind::Sym, keys: keys(k) };
+        assert_eq!(stmts.0.get(&Key::Set("mod".to_string())).unwrap(), "Mod4");
+        assert_eq!(stmts.0.get(&binding("default", "Mod4+Return")).unwrap(), "exec foot");
+        assert_eq!(stmts.0.get(&binding("default", "Shift+Mod4+q")).unwrap(), "--locked --no-repeat kill");
+        assert_eq!(stmts.0.get(&binding("resize", "Escape")).unwrap(), "mode default");
+        assert_eq!(stmts.0.get(&Key::Setting { mode: "default".to_string(), name: "output eDP-1 scale".to_string() }).unwrap(), "2");
+        assert_eq!(stmts.0.len(), 6);
+    }
+
+    #[test]
+    fn test_compare() {
+        let generated = Statements::parse("set $mod Mod4\
+            \nbindsym $mod+Return exec alacritty\
+            \nbindsym $mod+d exec rofi\
+            \nexec mako\
+            \nfont pango:Fira Code 10\n");
+        let live = Statements::parse("set $mod Mod4\
+            \nbindsym Mod4+Return   exec foot\
+            \nbindsym Mod4+x exec firefox\
+            \nexec 'mako'\
+            \nfont \"pango:Fira Code 10\"\n");
+        let diff: Vec<String> = compare(&generated, &live).iter().map(|d| d.to_string()).collect();
+        assert_eq!(diff, vec![
+            "~ bindsym Mod4+Return\n    generated: exec alacritty\n    live:      exec foot",
+            "- bindsym Mod4+d: exec rofi",
+            "+ bindsym Mod4+x: exec firefox",
+        ]);
+        assert!(compare(&generated, &generated).is_empty());
+    }
+
+    #[test]
+    fn test_equivalent_commands() {
+        let generated = Statements::parse("bindsym Mod4+f fullscreen toggle\
+            \nbindsym Mod4+1 move container to workspace 1\
+            \nbindsym Mod4+Shift+o move workspace to output DP-1\
+            \nbindsym Mod4+m mark --add a\
+            \nbindsym Mod4+v split vertical\
+            \nbindsym Mod4+b [app_id=\"foot\" con_mark=\"t\"] focus, sticky enable\
+            \nfocus_follows_mouse yes\
+            \nworkspace_auto_back_and_forth yes\
+            \nworkspace \"2 web\" output DP-1\
+            \noutput eDP-1 mode 1920x1080@60Hz position 0 0 scale 1.5 adaptive_sync on\n");
+        let live = Statements::parse("bindsym Mod4+f fullscreen\
+            \nbindsym Mod4+1 move window workspace 1\
+            \nbindsym Mod4+Shift+o move workspace output DP-1\
+            \nbindsym Mod4+m mark --add \"a\"\
+            \nbindsym Mod4+v splitv\
+            \nbindsym Mod4+b [con_mark=t app_id=foot] focus,sticky yes\
+            \nfocus_follows_mouse no\
+            \nfocus_follows_mouse true\
+            \nworkspace_auto_back_and_forth enable\
+            \nworkspace '2 web' output DP-1\
+            \noutput eDP-1 {\
+            \n    res 1920x1080@60Hz\
+            \n    pos 0 0\
+            \n    adaptive_sync enable\
+            \n    scale 1.5\
+            \n}\n");
+        assert_eq!(compare(&generated, &live), vec![]);
+
+        // a changed setting is a change, not an unrelated removal and addition
+        let live = Statements::parse("focus_follows_mouse always\nbindsym Mod4+1 move window to workspace 2\n");
+        let generated = Statements::parse("focus_follows_mouse yes\nbindsym Mod4+1 move container to workspace 1\n");
+        let diff: Vec<String> = compare(&generated, &live).iter().map(|d| d.to_string()).collect();
+        assert_eq!(diff, vec![
+            "~ bindsym Mod4+1\n    generated: move container to workspace 1\n    live:      move container to workspace 2",
+            "~ focus_follows_mouse\n    generated: enable\n    live:      always",
+        ]);
+    }
+}
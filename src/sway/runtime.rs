@@ -14,11 +14,14 @@
 // this program. If not, see <https://www.gnu.org/licenses/>.
 //
 
-use serde::{Deserialize, Serialize};
-use strum::Display;
+use std::fmt::{Display as FmtDisplay, Formatter, Result as FmtResult};
+use serde::{Deserialize, Serialize, Serializer};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::ser::SerializeMap;
+use strum::{Display, EnumDiscriminants};
 use crate::sway::{options};
 use crate::sway::criteria::CriteriaVec;
-use crate::sway::options::{bind, exec, focus, layout, mov, resize, ArgMap};
+use crate::sway::options::{bind, client, container, exec, floating, focus, gaps, layout, mark, mov, output, resize, ArgMap};
 
 /// Runtime commands for Sway.
 ///
@@ -32,81 +35,235 @@ use crate::sway::options::{bind, exec, focus, layout, mov, resize, ArgMap};
 /// This would bind `Mod4+Shift+Q` to the `kill` command if `kill` was a variant of [Runtime]. We
 /// get around this by representing parameterless commands in a separate enum ([ParamlessRuntime])
 /// that are stored in an untagged variant of [Runtime].
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+///
+/// Each variant also has a corresponding [RuntimeKind] (without any parameters), which is useful
+/// for grouping commands by type.
+#[derive(Debug, Clone, PartialEq, Eq, Display, EnumDiscriminants, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case", rename_all_fields = "kebab-case")]
 #[strum(serialize_all = "snake_case")]
+#[strum_discriminants(name(RuntimeKind), derive(Display, Hash, PartialOrd, Ord))]
+#[strum_discriminants(strum(serialize_all = "snake_case"))]
 pub enum Runtime {
+    /// Allow tearing (immediate presentation) for the focused window
     #[strum(to_string = "allow_tearing {0}")]
     AllowTearing(bool),
+    /// Bind key codes to a command
     #[strum(to_string = "bindsym {flags}{keys} {command}")]
     BindCode{
         #[serde(default, flatten)]
         flags: ArgMap<bind::Bind>,
         keys: bind::BindCodes,
         #[serde(flatten)]
+        #[schemars(schema_with = "runtime_ref")]
         command: Box<Runtime>
     },
+    /// Bind keys to a command
     #[strum(to_string = "bindsym {flags}{keys} {command}")]
     BindSym {
         #[serde(default)]
         flags: ArgMap<bind::Bind>,
         keys: bind::BindKeys,
         #[serde(flatten)]
+        #[schemars(schema_with = "runtime_ref")]
         command: Box<Runtime>
     },
+    /// Set the border style of the focused window
     #[strum(to_string = "border {0}")]
     Border(options::Border),
+    /// Set the colors of window borders and title bars
+    #[strum(to_string = "client.{0}")]
+    Client(client::ClientOpts),
+    /// Run a shell command
     #[strum(to_string = "exec {0}")]
     Exec(exec::ExecParams),
+    /// Run a shell command (again on every reload, when used in the config)
     #[strum(to_string = "exec_always {0}")]
     ExecAlways(exec::ExecParams),
+    /// Exit Sway
+    #[serde(serialize_with = "empty_table")]
     Exit,
+    /// Make the focused window floating or tiled
     #[strum(to_string = "floating {0}")]
     Floating(options::TogglableBool),
-    #[strum(to_string = "focus {0}")]
-    Focus(focus::FocusParams),
-    #[strum(to_string = "{0} focus")]
-    CriteriaFocus(CriteriaVec),
+    /// Set the modifier key used to drag floating windows with the mouse
+    #[strum(to_string = "floating_modifier {0}")]
+    FloatingModifier(floating::FloatingModifier),
+    /// Run several commands in a row
+    #[strum(to_string = "{0}")]
+    Chain(Chain),
+    /// Move focus (or focus the window matching the command's criteria)
+    #[strum(to_string = "{0}")]
+    Focus(focus::Focus),
+    /// Run a command whenever a window matching some criteria appears
     #[strum(to_string = "for_window {criteria} {command}")]
     ForWindow {
         criteria: CriteriaVec,
         #[serde(flatten)]
+        #[schemars(schema_with = "runtime_ref")]
         command: Box<Runtime>
     },
+    /// Make the focused window fullscreen (or not)
+    #[strum(to_string = "fullscreen {0}")]
+    Fullscreen(container::FullscreenParams),
+    /// Change the gaps of the current or all workspaces
+    #[strum(to_string = "gaps {0}")]
+    Gaps(gaps::GapsParams),
+    /// Prevent the system from going idle while a window is focused / visible / etc.
+    #[strum(to_string = "inhibit_idle {0}")]
+    InhibitIdle(container::InhibitIdle),
+    /// Close the focused window
+    #[serde(serialize_with = "empty_table")]
     Kill,
+    /// Change the layout of the focused container
     #[strum(to_string = "layout {0}")]
     Layout(layout::LayoutParams),
+    /// Run a command on the windows matching some criteria, instead of the focused window
+    #[strum(to_string = "{criteria} {command}")]
+    Matching {
+        criteria: CriteriaVec,
+        #[serde(flatten)]
+        #[schemars(schema_with = "runtime_ref")]
+        command: Box<Runtime>
+    },
+    /// Set how long before the next frame the focused window is rendered
     #[strum(to_string = "max_render_time {0}")]
     MaxRenderTime(options::MaxRenderTimeOpts),
+    /// Mark the focused window
+    #[strum(to_string = "mark {0}")]
+    Mark(mark::MarkParams),
+    /// Switch to a binding mode
     #[strum(to_string = "mode {0}")]
     Mode(String),
+    /// Move the focused container
     #[strum(to_string = "move {0}")]
     Move(mov::MoveParams),
+    /// Do nothing (useful to unbind keys)
+    #[serde(serialize_with = "empty_table")]
     Nop,
+    /// Set the opacity of the focused window
+    #[strum(to_string = "opacity {0}")]
+    Opacity(container::OpacityParams),
+    /// Configure an output
+    #[strum(to_string = "output {name} {params}")]
+    Output {
+        name: String,
+        #[serde(flatten)]
+        params: output::OutputParams
+    },
+    /// Reload the Sway config
+    #[serde(serialize_with = "empty_table")]
     Reload,
+    /// Rename a workspace
     #[strum(to_string = "rename workspace {0}")]
     Rename(options::RenameOpts),
+    /// Resize the focused container
     #[strum(to_string = "resize {0}")]
     Resize(resize::ResizeParams),
+    /// Show or hide windows on the scratchpad (only the matching ones when used with `matching`)
     #[strum(to_string = "scratchpad show")]
+    #[serde(serialize_with = "empty_table")]
     Scratchpad,
+    /// Allow or prevent windows from inhibiting keyboard shortcuts
     #[strum(to_string = "shortcuts_inhibitor {0}")]
     ShortcutsInhibitor(bool),
+    /// Split the focused container
     #[strum(to_string = "split {0}")]
     Split(options::Split),
+    /// Define a variable
     #[strum(to_string = "set ${name} {value}")]
     Set{ 
         name: String,
         value: String
     },
+    /// Make a floating window show up on every workspace
     #[strum(to_string = "sticky {0}")]
     Sticky(options::TogglableBool),
+    /// Swap the focused container with another one
     #[strum(to_string = "swap container with {0}")]
     Swap(options::Swap),
+    /// Set the format of window titles
     #[strum(to_string = "title_format {0}")]
-    TitleFormat(String),
+    TitleFormat(container::TitleFormat),
+    /// Remove a mark (or all marks)
+    #[strum(to_string = "{0}")]
+    Unmark(mark::UnmarkParams),
+    /// Set or clear the urgent state of the focused window, or whether it can set it itself
+    #[strum(to_string = "urgent {0}")]
+    Urgent(container::Urgent),
+    /// Switch to a workspace
     #[strum(to_string = "workspace {0}")]
-    Workspace(options::Workspace),
+    Workspace(options::WorkspaceParams),
+}
+
+/// How a command in a [Chain] is separated from the previous one.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Separator {
+    /// `;`: the next command runs on its own (criteria of previous commands don't apply to it)
+    #[default]
+    #[serde(alias = ";")]
+    Semicolon,
+    /// `,`: the next command applies to the windows matched by the previous command's criteria
+    #[serde(alias = ",")]
+    Comma,
+}
+
+/// A command in a [Chain], along with its separator from the previous command (ignored for the
+/// first command).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct Chained {
+    #[serde(default)]
+    separator: Separator,
+    #[serde(flatten)]
+    #[schemars(schema_with = "runtime_ref")]
+    command: Runtime,
+}
+
+/// A sequence of commands, such as `mark a; mode default`.
+///
+/// ```toml
+/// [bindsym]
+/// "$mod+a".chain = [{ mark = "a" }, { mode = "default" }]
+/// "$mod+b".chain = [
+///     { matching = { criteria = [{ app-id = "foot" }], focus = {} } },
+///     { separator = "comma", fullscreen = "enable" },
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct Chain(Vec<Chained>);
+
+impl Chain {
+    /// Chain commands with the given separator between each of them.
+    pub fn new(commands: Vec<Runtime>, separator: Separator) -> Self {
+        Chain(commands.into_iter().map(|command| Chained { separator: separator.clone(), command }).collect())
+    }
+}
+
+impl FmtDisplay for Chain {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for (i, c) in self.0.iter().enumerate() {
+            match (i, &c.separator) {
+                (0, _) => (),
+                (_, Separator::Semicolon) => write!(f, "; ")?,
+                (_, Separator::Comma) => write!(f, ", ")?,
+            }
+            write!(f, "{}", c.command)?;
+        }
+        Ok(())
+    }
+}
+
+/// Nested commands are flattened into their parent command, so their schema has to be a reference
+/// (inlining it would recurse forever).
+fn runtime_ref(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<Runtime>()
+}
+
+/// Serialize parameterless commands as an empty table (`kill = {}`), since TOML has no unit type.
+fn empty_table<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_map(Some(0))?.end()
 }
 
 #[cfg(test)]
@@ -137,4 +294,78 @@ mod tests {
         assert_eq!(cmd2.to_string(), "bindsym Mod4+X exec firefox");
         assert_eq!(cmd3.to_string(), "bindsym --exclude-titlebar Mod4+Shift exec ls -la ~");
     }
-}
\ No newline at end of file
+
+    /// Parse a single command and render it for Sway.
+    fn cmd(s: &str) -> Result<String, toml::de::Error> {
+        toml::from_str::<Runtime>(s).map(|r| r.to_string())
+    }
+
+    #[test]
+    fn test_container_commands() {
+        assert_eq!(cmd("fullscreen = \"toggle\"").unwrap(), "fullscreen toggle");
+        assert_eq!(cmd("fullscreen = {}").unwrap(), "fullscreen toggle");
+        assert_eq!(cmd("fullscreen = { mode = true, global = true }").unwrap(), "fullscreen enable global");
+        assert_eq!(cmd("opacity = 0.8").unwrap(), "opacity 0.8");
+        assert_eq!(cmd("opacity = 1").unwrap(), "opacity 1");
+        assert_eq!(cmd("opacity = { change = \"minus\", value = 0.05 }").unwrap(), "opacity minus 0.05");
+        assert!(cmd("opacity = 1.5").is_err());
+        assert_eq!(cmd("urgent = \"deny\"").unwrap(), "urgent deny");
+        assert_eq!(cmd("inhibit-idle = \"fullscreen\"").unwrap(), "inhibit_idle fullscreen");
+        assert_eq!(cmd("move = \"scratchpad\"").unwrap(), "move scratchpad");
+        assert_eq!(cmd("scratchpad = {}").unwrap(), "scratchpad show");
+        assert_eq!(cmd("matching = { criteria = [{ app-id = \"keepassxc\" }], scratchpad = {} }").unwrap(),
+                   "[app_id=\"keepassxc\"] scratchpad show");
+        assert_eq!(cmd("title-format = \"%title (%app_id)\"").unwrap(), "title_format %title (%app_id)");
+        assert!(cmd("title-format = \"%name\"").is_err());
+        assert_eq!(cmd("title-format = \"%title 100% %2\"").unwrap(), "title_format %title 100% %2");
+    }
+
+    #[test]
+    fn test_chains() {
+        assert_eq!(cmd("matching = { criteria = [{ con-mark = \"a\" }], focus = {} }").unwrap(),
+                   "[con_mark=\"a\"] focus");
+        assert_eq!(cmd("matching = { criteria = [{ app-id = \"foot\" }], kill = {} }").unwrap(),
+                   "[app_id=\"foot\"] kill");
+        assert_eq!(cmd("focus = \"tiling\"").unwrap(), "focus tiling");
+        assert_eq!(cmd("chain = [{ mark = \"a\" }, { mode = \"default\" }]").unwrap(), "mark a; mode default");
+        assert_eq!(cmd("chain = [\
+            \n{ matching = { criteria = [{ app-id = \"foot\" }], focus = {} } },\
+            \n{ separator = \"comma\", fullscreen = \"enable\" },\
+            \n{ separator = \";\", workspace = 2 },\
+            \n]").unwrap(), "[app_id=\"foot\"] focus, fullscreen enable; workspace 2");
+    }
+
+    #[test]
+    fn test_move() {
+        assert_eq!(cmd("move.directional = \"left\"").unwrap(), "move left");
+        assert_eq!(cmd("move.directional = { direction = \"up\", amount = 20, unit = \"ppt\" }").unwrap(), "move up 20 ppt");
+        assert_eq!(cmd("move.position = { x = 1920, y = 0 }").unwrap(), "move position 1920 px 0 px");
+        assert_eq!(cmd("move.position = \"center\"").unwrap(), "move position center");
+        assert_eq!(cmd("move.absolute-position = { x = -1920, y = 1080 }").unwrap(), "move absolute position -1920 px 1080 px");
+        assert_eq!(cmd("move.container = 1").unwrap(), "move container to workspace 1");
+        assert_eq!(cmd("move.container = { number = 3, name = \"web\" }").unwrap(), "move container to workspace number 3 web");
+        assert_eq!(cmd("move.container = \"scratchpad\"").unwrap(), "move container to scratchpad");
+        assert_eq!(cmd("move.window = { output = \"left\" }").unwrap(), "move container to output left");
+        assert_eq!(cmd("move.container = { to = { number = 4 }, no-auto-back-and-forth = true }").unwrap(),
+                   "move --no-auto-back-and-forth container to workspace number 4");
+        assert_eq!(cmd("move.container = { mark = \"a\" }").unwrap(), "move container to mark a");
+        assert_eq!(cmd("move.mark = \"a\"").unwrap(), "move to mark a");
+        assert_eq!(cmd("move.workspace = \"DP-1\"").unwrap(), "move workspace to output DP-1");
+        assert_eq!(cmd("move.workspace = [\"DP-1\", \"eDP-1\"]").unwrap(), "move workspace to output DP-1 eDP-1");
+    }
+
+    #[test]
+    fn test_resize() {
+        assert_eq!(cmd("resize.grow = { width = 10 }").unwrap(), "resize grow width 10 px");
+        assert_eq!(cmd("resize.grow = { width = 10, or = 5 }").unwrap(), "resize grow width 10 px or 5 ppt");
+        assert_eq!(cmd("resize.shrink = { height = 5, unit = \"ppt\", or = 20 }").unwrap(), "resize shrink height 5 ppt or 20 px");
+        assert_eq!(cmd("resize.grow = { direction = \"left\", amount = 300 }").unwrap(), "resize grow left 300 px");
+        assert_eq!(cmd("resize = { width = 1200, height = 800 }").unwrap(), "resize set width 1200 px height 800 px");
+        assert_eq!(cmd("resize = { width = 50, height = 800, width-unit = \"ppt\" }").unwrap(),
+                   "resize set width 50 ppt height 800 px");
+        assert_eq!(cmd("resize = { height = 600 }").unwrap(), "resize set height 600 px");
+        // keys that belong to another form are errors, instead of being dropped
+        assert!(cmd("resize = { width = 50, width-unit = \"ppt\" }").is_err());
+        assert!(cmd("resize.grow = { width = 10, height = 20 }").is_err());
+    }
+}
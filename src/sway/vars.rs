@@ -0,0 +1,311 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use toml::{Table, Value};
+
+/// Errors that can occur while resolving variables.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VarError {
+    #[error("Variable cycle detected: {}", .0.iter().map(|v| format!("${v}")).collect::<Vec<String>>().join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("Keys {0:?} and {1:?} are the same once variables are inlined")]
+    Collision(String, String),
+}
+
+/// Resolved set of Sway config variables (defined using `set`).
+///
+/// Sway replaces variables in a `set` value at the time it is defined, so nested variables (such as
+/// `$cfg_dir` inside `$lock_sh`) only work if they are defined first. This resolves every value up
+/// front and keeps track of an order that the `set` commands can safely be emitted in.
+///
+/// Replacement follows Sway's rules: at each `$`, the longest defined variable name that prefixes
+/// the rest of the string is substituted. Anything else is left as-is, but `$name` references that
+/// don't match any variable are recorded as undefined.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Variables {
+    /// Fully resolved variable values
+    values: HashMap<String, String>,
+    /// Variable names, ordered so that every variable comes after the ones it references
+    order: Vec<String>,
+    /// Names of undefined variables referenced by variable values
+    undefined: BTreeSet<String>,
+}
+
+impl Variables {
+    /// Resolve a map of variable names (without the leading `$`) to their raw values.
+    pub fn new(set: &HashMap<String, String>) -> Result<Self, VarError> {
+        let mut names: Vec<&String> = set.keys().collect();
+        names.sort();
+        let mut vars = Variables::default();
+        for name in names {
+            vars.resolve(name, set, &mut Vec::new())?;
+        }
+        Ok(vars)
+    }
+
+    /// Resolve the variables defined in the `[set]` table of a TOML document.
+    pub fn from_doc(doc: &Table) -> Result<Self, VarError> {
+        let set = match doc.get("set") {
+            Some(Value::Table(t)) => t.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        Self::new(&set)
+    }
+
+    fn resolve(&mut self, name: &str, set: &HashMap<String, String>, stack: &mut Vec<String>) -> Result<(), VarError> {
+        if self.values.contains_key(name) { return Ok(()) }
+        if let Some(pos) = stack.iter().position(|n| n == name) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            return Err(VarError::Cycle(cycle));
+        }
+        stack.push(name.to_string());
+        let raw = &set[name];
+        for dep in references(raw, set.keys()) {
+            self.resolve(&dep, set, stack)?;
+        }
+        stack.pop();
+
+        let (value, undefined) = self.replace(raw);
+        self.undefined.extend(undefined.into_iter().map(|(_, name)| name));
+        self.values.insert(name.to_string(), value);
+        self.order.push(name.to_string());
+        Ok(())
+    }
+
+    /// Replace all variables in a string, returning the result and any undefined references (along
+    /// with where their `$` is in `s`).
+    fn replace(&self, s: &str) -> (String, Vec<(usize, String)>) {
+        let mut result = String::with_capacity(s.len());
+        let mut undefined = Vec::new();
+        let mut rest = s;
+        while let Some(pos) = rest.find('$') {
+            result.push_str(&rest[..pos]);
+            let after = &rest[pos + 1..];
+            match longest_match(after, self.values.keys()) {
+                Some(name) => {
+                    result.push_str(&self.values[name]);
+                    rest = &after[name.len()..];
+                }
+                None => {
+                    if let Some(name) = identifier(after) { undefined.push((s.len() - after.len() - 1, name.to_string())) }
+                    result.push('$');
+                    rest = after;
+                }
+            }
+        }
+        result.push_str(rest);
+        (result, undefined)
+    }
+
+    /// Replace all variables in a string, leaving undefined variables as-is (like Sway does).
+    pub fn substitute(&self, s: &str) -> String {
+        self.replace(s).0
+    }
+
+    /// Where the references to undefined variables in a string are, as `$name` byte ranges. This
+    /// follows the same longest-match rule as substitution, so `$mode_system` is not a reference
+    /// to `$mod` when `$mode_system` is defined.
+    pub fn undefined_spans(&self, s: &str) -> Vec<(usize, usize, String)> {
+        self.replace(s).1.into_iter().map(|(pos, name)| (pos, pos + name.len() + 1, name)).collect()
+    }
+
+    /// Variable names in an order that is safe to emit `set` commands in.
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// The resolved value of a variable.
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.values.get(name)
+    }
+
+    /// Undefined variables referenced by the variable values themselves.
+    pub fn undefined(&self) -> impl Iterator<Item = &String> {
+        self.undefined.iter()
+    }
+}
+
+/// Keys whose values are commands run by a shell, where `$NAME` usually refers to a shell
+/// variable rather than a `set` variable.
+const EXEC_KEYS: [&str; 2] = ["exec", "exec-always"];
+
+/// Names of undefined variables referenced by the keys and string values of a TOML document
+/// (including variable values). Exec commands are skipped, since Sway leaves unknown `$NAME`s in
+/// them for the shell to expand.
+pub fn undefined(doc: &Table, vars: &Variables) -> BTreeSet<String> {
+    let mut undefined: BTreeSet<String> = vars.undefined().cloned().collect();
+    visit_strings(doc, false, &mut |s| undefined.extend(vars.replace(s).1.into_iter().map(|(_, name)| name)));
+    undefined
+}
+
+fn visit_strings(table: &Table, exec: bool, f: &mut impl FnMut(&str)) {
+    for (k, v) in table {
+        if !exec { f(k) }
+        visit_value(v, exec || EXEC_KEYS.contains(&k.as_str()), f);
+    }
+}
+
+fn visit_value(value: &Value, exec: bool, f: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(s) if !exec => f(s),
+        Value::Array(a) => a.iter().for_each(|v| visit_value(v, exec, f)),
+        Value::Table(t) => visit_strings(t, exec, f),
+        _ => (),
+    }
+}
+
+/// Inline all variables into every key and string value of a TOML document.
+///
+/// The `[set]` table is removed, since there is nothing left to define once everything has been
+/// inlined. Undefined variables are left as-is, like Sway does (see [undefined]). Fails if two keys
+/// in the same table (such as `"$mod+Return"` and `"Mod4+Return"`) become the same key.
+pub fn inline(doc: &mut Table, vars: &Variables) -> Result<(), VarError> {
+    doc.remove("set");
+    *doc = inline_table(std::mem::take(doc), vars)?;
+    Ok(())
+}
+
+fn inline_table(table: Table, vars: &Variables) -> Result<Table, VarError> {
+    let mut originals: HashMap<String, String> = HashMap::new();
+    let mut inlined = Table::new();
+    for (k, v) in table {
+        let key = vars.substitute(&k);
+        if let Some(other) = originals.insert(key.clone(), k.clone()) {
+            return Err(VarError::Collision(other, k));
+        }
+        inlined.insert(key, inline_value(v, vars)?);
+    }
+    Ok(inlined)
+}
+
+fn inline_value(value: Value, vars: &Variables) -> Result<Value, VarError> {
+    Ok(match value {
+        Value::String(s) => Value::String(vars.substitute(&s)),
+        Value::Array(a) => Value::Array(a.into_iter().map(|v| inline_value(v, vars)).collect::<Result<_, _>>()?),
+        Value::Table(t) => Value::Table(inline_table(t, vars)?),
+        v => v,
+    })
+}
+
+/// Names of the variables in `names` that are referenced by `s`.
+fn references<'a>(s: &str, names: impl Iterator<Item = &'a String> + Clone) -> Vec<String> {
+    s.match_indices('$')
+        .filter_map(|(pos, _)| longest_match(&s[pos + 1..], names.clone()))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn longest_match<'a>(s: &str, names: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+    names.filter(|n| !n.is_empty() && s.starts_with(n.as_str())).max_by_key(|n| n.len())
+}
+
+fn identifier(s: &str) -> Option<&str> {
+    let end = s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(s.len());
+    match s.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => Some(&s[..end]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_nested() {
+        let vars = Variables::new(&set(&[
+            ("lock_sh", "$cfg_dir/scripts/lock.sh"),
+            ("cfg_dir", "/home/me/.config/sway"),
+            ("mod", "Mod4"),
+            ("modifier", "Shift"),
+        ])).unwrap();
+        assert_eq!(vars.get("lock_sh").unwrap(), "/home/me/.config/sway/scripts/lock.sh");
+        let order = vars.order();
+        let pos = |n: &str| order.iter().position(|o| o == n).unwrap();
+        assert!(pos("cfg_dir") < pos("lock_sh"));
+        assert_eq!(vars.substitute("$mod+$modifier+Return"), "Mod4+Shift+Return");
+        assert_eq!(vars.substitute("grim -g \"$(slurp -d)\""), "grim -g \"$(slurp -d)\"");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let err = Variables::new(&set(&[("a", "$b"), ("b", "$c"), ("c", "x $a")])).unwrap_err();
+        assert_eq!(err, VarError::Cycle(vec!["a".to_string(), "b".to_string(), "c".to_string(), "a".to_string()]));
+    }
+
+    #[test]
+    fn test_undefined() {
+        let vars = Variables::new(&set(&[("bg", "$HOME/bg.png")])).unwrap();
+        assert_eq!(vars.undefined().collect::<Vec<&String>>(), vec!["HOME"]);
+        assert_eq!(vars.substitute("$mod+x"), "$mod+x");
+
+        let vars = Variables::new(&set(&[("mode_system", "System")])).unwrap();
+        assert_eq!(vars.undefined_spans("$mod+$mode_system+$mod"),
+                   vec![(0, 4, "mod".to_string()), (18, 22, "mod".to_string())]);
+    }
+
+    #[test]
+    fn test_inline() {
+        let mut doc: Table = toml::from_str("exec = [\"swaybg -i $bg\"]\
+            \n[set]\
+            \nmod = \"Mod4\"\
+            \nbg = \"/tmp/bg.png\"\
+            \n[bindsym]\
+            \n\"$mod+Return\".exec = \"alacritty\"").unwrap();
+        let vars = Variables::from_doc(&doc).unwrap();
+        inline(&mut doc, &vars).unwrap();
+        assert!(doc.get("set").is_none());
+        assert_eq!(doc["exec"][0].as_str(), Some("swaybg -i /tmp/bg.png"));
+        assert!(doc["bindsym"].as_table().unwrap().contains_key("Mod4+Return"));
+    }
+
+    #[test]
+    fn test_inline_collision() {
+        let mut doc: Table = toml::from_str("[set]\
+            \nmod = \"Mod4\"\
+            \n[bindsym]\
+            \n\"$mod+Return\".exec = \"foot\"\
+            \n\"Mod4+Return\".exec = \"alacritty\"").unwrap();
+        let vars = Variables::from_doc(&doc).unwrap();
+        assert_eq!(inline(&mut doc, &vars), Err(VarError::Collision("$mod+Return".to_string(), "Mod4+Return".to_string())));
+    }
+
+    #[test]
+    fn test_undefined_in_doc() {
+        let mut doc: Table = toml::from_str("exec = [\"swaybg -i $HOME/$bg\"]\
+            \n[set]\
+            \nbg = \"bg.png\"\
+            \nlock = \"$XDG_RUNTIME_DIR/lock\"\
+            \n[bindsym]\
+            \n\"$mod+Return\".exec = \"$TERMINAL\"\
+            \n\"$mod+d\".exec = { command = \"$launcher\" }\
+            \n\"$mod+q\".mode = \"$mode_system\"").unwrap();
+        let vars = Variables::from_doc(&doc).unwrap();
+        assert_eq!(undefined(&doc, &vars).into_iter().collect::<Vec<String>>(), vec!["XDG_RUNTIME_DIR", "mod", "mode_system"]);
+        inline(&mut doc, &vars).unwrap();
+        assert_eq!(doc["exec"][0].as_str(), Some("swaybg -i $HOME/bg.png"));
+        assert_eq!(doc["bindsym"]["$mod+Return"]["exec"].as_str(), Some("$TERMINAL"));
+    }
+}
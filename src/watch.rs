@@ -0,0 +1,180 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How long files have to stay unchanged before a change is reported. Editors often write files in
+/// several steps, so this avoids regenerating from a half-written file.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Modification times of every watched file. Missing files are recorded as [None], so that
+/// deleting or creating a file also counts as a change.
+type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// Convert a path that may contain glob patterns (such as Sway's `include ~/.config/sway/*`) into a
+/// path that can be watched. Patterns are replaced by their closest parent directory.
+pub fn watchable(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        if component.as_os_str().to_string_lossy().contains(['*', '?', '[']) { break }
+        result.push(component);
+    }
+    result
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Take a snapshot of the given paths. Directories are watched through their immediate contents
+/// (their own modification time also changes when an ignored file is written into them).
+fn snapshot(paths: &[PathBuf], ignored: &[PathBuf]) -> Snapshot {
+    let mut snap = Snapshot::new();
+    for path in paths {
+        match fs::read_dir(path) {
+            Ok(entries) => for entry in entries.flatten() {
+                snap.insert(entry.path(), modified(&entry.path()));
+            },
+            Err(_) => { snap.insert(path.clone(), modified(path)); }
+        }
+    }
+    snap.retain(|p, _| !ignored.iter().any(|i| is_ignored(p, i)));
+    snap
+}
+
+/// Whether `path` is the ignored file itself, or one of the files written next to it while
+/// writing it (`<file>.bak.<time>` backups and the `<file>.swayconf-tmp` temporary file).
+fn is_ignored(path: &Path, ignored: &Path) -> bool {
+    if path == ignored { return true }
+    if path.parent() != ignored.parent() { return false }
+    let (Some(name), Some(ignored)) = (path.file_name(), ignored.file_name()) else { return false };
+    let (name, ignored) = (name.to_string_lossy(), ignored.to_string_lossy());
+    match name.strip_prefix(&*ignored) {
+        Some(rest) => rest == ".swayconf-tmp" || rest.starts_with(".bak."),
+        None => false,
+    }
+}
+
+/// Polls a set of files for changes.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    /// Files that are never reported as changed (such as the file we are writing to), along with
+    /// their backups and temporary files.
+    ignored: Vec<PathBuf>,
+    last: Snapshot,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>, ignored: Vec<PathBuf>) -> Self {
+        let paths: Vec<PathBuf> = paths.iter().map(|p| watchable(p)).collect();
+        let last = snapshot(&paths, &ignored);
+        Watcher { paths, ignored, last }
+    }
+
+    /// Replace the set of watched files (for example, after the list of included files changed).
+    pub fn set_paths(&mut self, paths: Vec<PathBuf>) {
+        let paths: Vec<PathBuf> = paths.iter().map(|p| watchable(p)).collect();
+        if paths != self.paths {
+            log::debug!("Now watching {:?}", paths);
+            self.last = snapshot(&paths, &self.ignored);
+            self.paths = paths;
+        }
+    }
+
+    /// Check whether anything changed since the last call, without waiting.
+    pub fn changed(&mut self) -> bool {
+        let current = snapshot(&self.paths, &self.ignored);
+        let changed = current != self.last;
+        self.last = current;
+        changed
+    }
+
+    /// Block until something changes, and then until things have settled down.
+    pub fn wait(&mut self) {
+        while !self.changed() { thread::sleep(POLL_INTERVAL) }
+        loop {
+            thread::sleep(DEBOUNCE);
+            if !self.changed() { break }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_watchable() {
+        assert_eq!(watchable(Path::new("/etc/sway/config.d/*")), PathBuf::from("/etc/sway/config.d"));
+        assert_eq!(watchable(Path::new("/home/me/config.toml")), PathBuf::from("/home/me/config.toml"));
+    }
+
+    #[test]
+    fn test_changed() {
+        let dir = temp_dir("watch-changed");
+        let file = dir.join("config.toml");
+        fs::write(&file, "exec = []").unwrap();
+        let included = dir.join("config.d");
+        fs::create_dir(&included).unwrap();
+
+        let output = included.join("config");
+        let mut watcher = Watcher::new(vec![file.clone(), included.join("*")], vec![output.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&output, "exec mako").unwrap();
+        fs::write(included.join("config.bak.1"), "exec mako").unwrap();
+        assert!(!watcher.changed());
+
+        let f = fs::File::options().write(true).open(&file).unwrap();
+        f.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::write(included.join("colors"), "client.focused #000000 #000000 #ffffff").unwrap();
+        assert!(watcher.changed());
+
+        fs::remove_file(&file).unwrap();
+        assert!(watcher.changed());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_same_directory() {
+        // the default output path is the input path without `.toml`, so it is a prefix of the input
+        let dir = temp_dir("watch-same-dir");
+        let file = dir.join("config.toml");
+        let output = dir.join("config");
+        fs::write(&file, "exec = []").unwrap();
+        let mut watcher = Watcher::new(vec![dir.clone()], vec![output.clone()]);
+
+        fs::write(&output, "exec mako").unwrap();
+        fs::write(dir.join("config.bak.1"), "exec mako").unwrap();
+        fs::write(dir.join("config.swayconf-tmp"), "exec mako").unwrap();
+        assert!(!watcher.changed());
+
+        let f = fs::File::options().write(true).open(&file).unwrap();
+        f.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert!(watcher.changed());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
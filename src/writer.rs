@@ -0,0 +1,236 @@
+// <one line to give the program's name and a brief idea of what it does.>
+// Copyright (C) 2024, 2025 Dustin Thomas <stdio@cptlobster.dev>
+//
+// This program is free software: you can redistribute it and/or modify it under
+// the terms of the GNU General Public License as published by the Free Software
+// Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS
+// FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+//
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use similar::TextDiff;
+use thiserror::Error;
+
+/// How many backups of a config file are kept around. Older backups are deleted.
+const MAX_BACKUPS: usize = 5;
+
+#[derive(Debug, Error)]
+pub enum WriteError {
+    #[error("{0}")]
+    Io(#[from] IoError),
+    #[error("Sway rejected the generated config:\n{0}")]
+    Invalid(String),
+    #[error("No backups of {0} found")]
+    NoBackup(String),
+}
+
+/// Options for [write].
+#[derive(Debug, Clone, Default)]
+pub struct WriteOpts {
+    /// If set, this binary is run as `<binary> --validate -c <file>` on the new config before it
+    /// replaces the old one (normally this is `sway`).
+    pub validate_with: Option<PathBuf>,
+}
+
+/// Build a path next to `path` by appending a suffix to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name: OsString = path.file_name().unwrap_or_default().to_owned();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn backup_prefix(path: &Path) -> String {
+    format!("{}.bak.", path.file_name().unwrap_or_default().to_string_lossy())
+}
+
+/// All backups of `path`, oldest first.
+pub fn backups(path: &Path) -> Result<Vec<PathBuf>, IoError> {
+    let prefix = backup_prefix(path);
+    let dir = match path.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
+    let mut found: Vec<(u128, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(stamp) = name.strip_prefix(&prefix).and_then(|s| s.parse().ok()) {
+            found.push((stamp, entry.path()));
+        }
+    }
+    found.sort();
+    Ok(found.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Copy the current contents of `path` to a new timestamped backup, and delete the oldest backups
+/// beyond [MAX_BACKUPS].
+fn backup(path: &Path) -> Result<PathBuf, IoError> {
+    let mut stamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let mut target = sibling(path, &format!(".bak.{stamp}"));
+    // two writes within the same millisecond should not overwrite each other's backups
+    while target.exists() {
+        stamp += 1;
+        target = sibling(path, &format!(".bak.{stamp}"));
+    }
+    fs::copy(path, &target)?;
+    let all = backups(path)?;
+    for old in all.iter().take(all.len().saturating_sub(MAX_BACKUPS)) {
+        log::debug!("Removing old backup {}", old.display());
+        fs::remove_file(old)?;
+    }
+    Ok(target)
+}
+
+fn validate(binary: &Path, file: &Path) -> Result<(), WriteError> {
+    log::info!("Validating {} with {}", file.display(), binary.display());
+    let output = Command::new(binary).arg("--validate").arg("-c").arg(file).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let mut msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if msg.is_empty() { msg = output.status.to_string() }
+        Err(WriteError::Invalid(msg))
+    }
+}
+
+/// Replace the contents of `path`, so that it is never left half-written or invalid:
+/// 1. The new contents are written to a temporary file in the same directory.
+/// 2. If enabled, the temporary file is validated (see [WriteOpts::validate_with]).
+/// 3. If the contents changed, the current file is backed up.
+/// 4. The temporary file is renamed over `path`.
+///
+/// Returns the path of the backup, if one was made.
+pub fn write(path: &Path, contents: &str, opts: &WriteOpts) -> Result<Option<PathBuf>, WriteError> {
+    let tmp = sibling(path, ".swayconf-tmp");
+    let result = write_tmp(path, &tmp, contents, opts);
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn write_tmp(path: &Path, tmp: &Path, contents: &str, opts: &WriteOpts) -> Result<Option<PathBuf>, WriteError> {
+    let mut file = File::create(tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    if let Some(binary) = &opts.validate_with {
+        validate(binary, tmp)?;
+    }
+    let backup = match fs::read_to_string(path) {
+        Ok(old) if old != contents => Some(backup(path)?),
+        _ => None,
+    };
+    fs::rename(tmp, path)?;
+    Ok(backup)
+}
+
+/// Compare `contents` to the current contents of `path` (a missing file counts as empty). Returns a
+/// unified diff from the current to the new contents if they differ.
+pub fn diff(path: &Path, contents: &str) -> Result<Option<String>, IoError> {
+    let old = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    if old == contents {
+        return Ok(None)
+    }
+    let name = path.display().to_string();
+    Ok(Some(TextDiff::from_lines(&old, contents)
+        .unified_diff()
+        .header(&name, &format!("{name} (generated)"))
+        .to_string()))
+}
+
+/// Restore the most recent backup of `path`. The backup is consumed, so rolling back again restores
+/// the one before it. Returns the path of the restored backup.
+pub fn rollback(path: &Path) -> Result<PathBuf, WriteError> {
+    let latest = backups(path)?.pop()
+        .ok_or_else(|| WriteError::NoBackup(path.display().to_string()))?;
+    log::info!("Restoring {} from {}", path.display(), latest.display());
+    fs::rename(&latest, path)?;
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A stand-in for `sway --validate` that rejects any config containing "bad".
+    fn stub_sway(dir: &Path) -> PathBuf {
+        let stub = dir.join("sway");
+        fs::write(&stub, "#!/bin/sh\nif grep -q bad \"$3\"; then echo \"Error: bad command\" >&2; exit 1; fi\n").unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    #[test]
+    fn test_write_and_rollback() {
+        let dir = temp_dir("writer-rollback");
+        let config = dir.join("config");
+        let opts = WriteOpts::default();
+
+        assert_eq!(write(&config, "exec mako\n", &opts).unwrap(), None);
+        assert!(write(&config, "exec kanshi\n", &opts).unwrap().is_some());
+        // unchanged contents don't need a backup
+        assert_eq!(write(&config, "exec kanshi\n", &opts).unwrap(), None);
+        assert_eq!(fs::read_to_string(&config).unwrap(), "exec kanshi\n");
+        assert_eq!(backups(&config).unwrap().len(), 1);
+
+        rollback(&config).unwrap();
+        assert_eq!(fs::read_to_string(&config).unwrap(), "exec mako\n");
+        assert!(matches!(rollback(&config), Err(WriteError::NoBackup(_))));
+
+        for i in 0..MAX_BACKUPS + 2 {
+            write(&config, &format!("exec {i}\n"), &opts).unwrap();
+        }
+        assert_eq!(backups(&config).unwrap().len(), MAX_BACKUPS);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        let dir = temp_dir("writer-diff");
+        let config = dir.join("config");
+        assert!(diff(&config, "exec mako\n").unwrap().unwrap().contains("+exec mako\n"));
+
+        fs::write(&config, "exec mako\nexec kanshi\n").unwrap();
+        assert_eq!(diff(&config, "exec mako\nexec kanshi\n").unwrap(), None);
+        let d = diff(&config, "exec mako\nexec waybar\n").unwrap().unwrap();
+        assert!(d.contains(" exec mako\n-exec kanshi\n+exec waybar\n"));
+        // nothing is written
+        assert_eq!(fs::read_to_string(&config).unwrap(), "exec mako\nexec kanshi\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validation() {
+        let dir = temp_dir("writer-validate");
+        let config = dir.join("config");
+        let opts = WriteOpts { validate_with: Some(stub_sway(&dir)) };
+
+        write(&config, "exec mako\n", &opts).unwrap();
+        match write(&config, "bad\n", &opts) {
+            Err(WriteError::Invalid(msg)) => assert_eq!(msg, "Error: bad command"),
+            other => panic!("expected validation failure, got {other:?}"),
+        }
+        // the old config is untouched, and nothing is left behind
+        assert_eq!(fs::read_to_string(&config).unwrap(), "exec mako\n");
+        assert!(backups(&config).unwrap().is_empty());
+        assert!(!sibling(&config, ".swayconf-tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}